@@ -2,6 +2,9 @@ use bevy::prelude::*;
 use bevy_gl::libs::{
     app::app_default,
     camera::{
+        camera_bookmarks::CameraBookmarksConfig,
+        camera_collision::{CameraBounds, CameraCollider, CameraGround},
+        camera_config::CameraConfig,
         camera_plugin::{AddCameraOpts, CameraTrait},
         camera_view::CameraViewOpts,
     },
@@ -18,6 +21,23 @@ fn main() {
                 yaw: -100.0,
                 ..Default::default()
             },
+            config: CameraConfig {
+                smoothing: Some(Default::default()),
+                bounds: Some(CameraBounds {
+                    min: Vec3::new(-10.0, 0.0, -10.0),
+                    max: Vec3::new(10.0, 10.0, 10.0),
+                }),
+                ground: Some(CameraGround {
+                    y: 0.0,
+                    min_height: 0.3,
+                }),
+                collision_radius: Some(0.3),
+                ..Default::default()
+            },
+            bookmarks: Some(CameraBookmarksConfig {
+                feat_id: "feat_camera",
+                ..Default::default()
+            }),
             ..Default::default()
         })
         .run();
@@ -40,6 +60,7 @@ fn setup(
             translation: Translation::new(0.0, 1.0, 0.0),
             ..Default::default()
         })
+        .with(CameraCollider::new(Vec3::splat(0.5)))
         .spawn(LightComponents {
             translation: Translation::new(4.0, 8.0, 4.0),
             ..Default::default()
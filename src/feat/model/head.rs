@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use bevy_gl::libs::{
     camera::{
+        camera_bookmarks::CameraBookmarksConfig,
         camera_plugin::{AddCameraOpts, CameraTrait},
         camera_view::CameraViewOpts,
     },
@@ -20,6 +21,10 @@ fn main() {
                 yaw: -72.40,
                 ..Default::default()
             },
+            bookmarks: Some(CameraBookmarksConfig {
+                feat_id: "feat_model_head",
+                ..Default::default()
+            }),
             ..Default::default()
         })
         .run();
@@ -1,5 +1,14 @@
 use bevy::prelude::*;
-use bevy_gl::libs::{camera::camera_plugin::CameraTrait, util::load_texture_material};
+use bevy_gl::libs::{
+    camera::{
+        camera_focus::{CameraFocusConfig, FocusCamera},
+        camera_follow::CameraFollow,
+        camera_path::CameraPathConfig,
+        camera_plugin::{AddCameraOpts, CameraTrait},
+        camera_switch::ActiveCamera,
+    },
+    util::load_texture_material,
+};
 
 // Source: https://sketchfab.com/3d-models/pony-cartoon-885d9f60b3a9429bb4077cfac5653cf9
 
@@ -8,10 +17,69 @@ fn main() {
         .add_resource(Msaa { samples: 4 })
         .add_default_plugins()
         .add_startup_system(setup.system())
-        .add_camera()
+        .init_resource::<MeshEvents>()
+        .add_system(focus_when_loaded.system())
+        .add_system(toggle_chase_camera.system())
+        .add_camera_from(AddCameraOpts {
+            path: Some(CameraPathConfig {
+                feat_id: "feat_model_pony_cartoon",
+                ..Default::default()
+            }),
+            focus: Some(CameraFocusConfig::default()),
+            ..Default::default()
+        })
         .run();
 }
 
+#[derive(Default)]
+struct MeshEvents {
+    reader: EventReader<AssetEvent<Mesh>>,
+}
+
+// The model is loaded in the background, so we can only frame it once its mesh arrived
+fn focus_when_loaded(
+    mut mesh_events: ResMut<MeshEvents>,
+    mesh_asset_events: Res<Events<AssetEvent<Mesh>>>,
+    mut focus_events: ResMut<Events<FocusCamera>>,
+) {
+    for event in mesh_events.reader.iter(&mesh_asset_events) {
+        if let AssetEvent::Created { .. } = event {
+            focus_events.send(FocusCamera { target: None });
+        }
+    }
+}
+
+/// Marks the car the chase camera follows
+struct Car;
+
+// The model is Z up, which is why it's rotated by -90° around X, so behind and above the car
+// is along -Y and +Z of the car
+fn toggle_chase_camera(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    active_camera: Res<ActiveCamera>,
+    mut car_query: Query<(Entity, &Car)>,
+    follow_query: Query<&CameraFollow>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::T) {
+        return;
+    }
+    let camera = match active_camera.entity() {
+        Some(camera) => camera,
+        None => return,
+    };
+    if follow_query.get::<CameraFollow>(camera).is_ok() {
+        commands.remove_one::<CameraFollow>(camera);
+        return;
+    }
+    for (car, _) in &mut car_query.iter() {
+        commands.insert_one(
+            camera,
+            CameraFollow::new(car, Vec3::new(0.0, -8.0, 3.0), 0.3),
+        );
+    }
+}
+
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -43,6 +111,7 @@ fn setup(
             material: body_material,
             ..Default::default()
         })
+        .with(Car)
         // light
         .spawn(LightComponents {
             translation: Translation::new(4.0, 5.0, 4.0),
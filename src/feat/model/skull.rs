@@ -1,5 +1,12 @@
 use bevy::prelude::*;
-use bevy_gl::libs::{camera::camera_plugin::CameraTrait, util::load_texture_material};
+use bevy_gl::libs::{
+    camera::{
+        camera_orbit::CameraOrbitOpts,
+        camera_path::CameraPathConfig,
+        camera_plugin::{AddCameraOpts, CameraMode, CameraTrait},
+    },
+    util::load_texture_material,
+};
 
 // Source: https://sketchfab.com/3d-models/skull-downloadable-1a9db900738d44298b0bc59f68123393
 fn main() {
@@ -7,7 +14,18 @@ fn main() {
         .add_resource(Msaa { samples: 4 })
         .add_default_plugins()
         .add_startup_system(setup.system())
-        .add_camera()
+        .add_camera_from(AddCameraOpts {
+            mode: CameraMode::Orbit(CameraOrbitOpts {
+                target: Vec3::new(0.0, 0.0, -1.0),
+                distance: 4.0,
+                ..Default::default()
+            }),
+            path: Some(CameraPathConfig {
+                feat_id: "feat_model_skull",
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
         .run();
 }
 
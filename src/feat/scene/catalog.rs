@@ -0,0 +1,560 @@
+use crate::libs::{
+    camera::{
+        camera_bindings::key_code_from_str,
+        camera_picking::{mesh_aabb, Pickable},
+    },
+    shape::Triangle,
+    util::load_from,
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::{error::Error, fs, time::SystemTime};
+
+/// Gap between placed objects and the floor so the two don't fight over which one is drawn
+const FLOOR_GAP: f32 = 0.01;
+
+/// Marks objects placed with the editor.
+/// The entity changes when an object is deleted and restored via undo, `id` stays the same.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EditorObject {
+    pub id: u32,
+    /// `CatalogEntry::id` of the entry the object was spawned from
+    pub entry: u128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: f32,
+}
+
+impl Pose {
+    pub fn new(translation: &Translation, rotation: &Rotation, scale: &Scale) -> Self {
+        Pose {
+            translation: translation.0,
+            rotation: rotation.0,
+            scale: scale.0,
+        }
+    }
+
+    pub fn at(translation: Vec3) -> Self {
+        Pose {
+            translation,
+            rotation: Quat::identity(),
+            scale: 1.0,
+        }
+    }
+
+    pub fn write(&self, translation: &mut Translation, rotation: &mut Rotation, scale: &mut Scale) {
+        translation.0 = self.translation;
+        rotation.0 = self.rotation;
+        scale.0 = self.scale;
+    }
+}
+
+/// Everything needed to spawn an object again after it was deleted
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ObjectState {
+    pub object: EditorObject,
+    pub pose: Pose,
+}
+
+/// 128 bit FNV-1a hash of `kind` and `name`.
+/// Unlike `DefaultHasher` it is the same across runs and builds, so scenes that were persisted
+/// with handles derived from it still resolve.
+pub fn catalog_id(kind: &str, name: &str) -> u128 {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+    kind.bytes()
+        .chain(std::iter::once(b':'))
+        .chain(name.bytes())
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u128).wrapping_mul(PRIME)
+        })
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub enum MeshKind {
+    Cube {
+        size: f32,
+    },
+    Sphere {
+        radius: f32,
+        subdivisions: usize,
+    },
+    Plane {
+        size: f32,
+    },
+    Triangle {
+        width: f32,
+        height: f32,
+    },
+    /// Mesh of a glTF file, relative to the working directory
+    Gltf {
+        path: String,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MaterialFile {
+    pub color: (f32, f32, f32),
+    /// Image file that is multiplied with `color`
+    pub texture: Option<String>,
+}
+
+impl Default for MaterialFile {
+    fn default() -> Self {
+        MaterialFile {
+            color: (0.5, 0.4, 0.3),
+            texture: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct CatalogEntryFile {
+    /// Identifies the entry, its mesh and material handles are derived from it
+    pub name: String,
+    /// Spawns the entry where the cursor points at the floor, i.e. "Key1"
+    #[serde(default)]
+    pub key: Option<String>,
+    pub mesh: MeshKind,
+    #[serde(default)]
+    pub material: MaterialFile,
+}
+
+/// Spawn catalog as stored in RON, i.e.
+/// `(entries: [(name: "Cube", key: Some("Key1"), mesh: Cube(size: 1.0))])`
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct CatalogFile {
+    pub entries: Vec<CatalogEntryFile>,
+}
+
+impl CatalogFile {
+    pub fn from_ron(ron: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(ron::de::from_str(ron)?)
+    }
+}
+
+pub struct CatalogEntry {
+    /// `catalog_id` of the entry's name
+    pub id: u128,
+    pub name: String,
+    pub key: Option<KeyCode>,
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+    /// Height above the floor the entry is placed at so that it rests on it
+    pub lift: f32,
+}
+
+/// Entry with the assets it was built with, kept apart until every entry of the catalog was
+/// built, so a catalog that fails to load leaves the current assets alone
+struct BuiltEntry {
+    entry: CatalogEntry,
+    mesh: Mesh,
+    material: StandardMaterial,
+    texture: Option<Texture>,
+}
+
+impl CatalogEntry {
+    /// Builds the entry's mesh, material and texture without storing them.
+    /// Loaded files are taken out of the asset storage right away.
+    fn build(
+        file: CatalogEntryFile,
+        asset_server: &AssetServer,
+        meshes: &mut Assets<Mesh>,
+        textures: &mut Assets<Texture>,
+    ) -> Result<BuiltEntry, Box<dyn Error>> {
+        let key = match &file.key {
+            Some(name) => Some(key_code_from_str(name).ok_or_else(|| {
+                format!(
+                    "Unknown key '{}' for '{}' in spawn catalog",
+                    name, file.name
+                )
+            })?),
+            None => None,
+        };
+
+        let mesh = match file.mesh {
+            MeshKind::Cube { size } => Mesh::from(shape::Cube { size }),
+            MeshKind::Sphere {
+                radius,
+                subdivisions,
+            } => Mesh::from(shape::Icosphere {
+                radius,
+                subdivisions,
+            }),
+            MeshKind::Plane { size } => Mesh::from(shape::Plane { size }),
+            MeshKind::Triangle { width, height } => Mesh::from(Triangle {
+                size: Vec2::new(width, height),
+            }),
+            MeshKind::Gltf { path } => {
+                // The asset server picks a new handle for each load, the mesh is moved to ours
+                // once the whole catalog was built
+                let loaded = asset_server.load_sync(meshes, &path)?;
+                meshes
+                    .remove(&loaded)
+                    .ok_or_else(|| format!("'{}' has no mesh", path))?
+            }
+        };
+        let lift = mesh_aabb(&mesh).map_or(0.0, |(min, _)| -min.y()) + FLOOR_GAP;
+
+        let texture = match &file.material.texture {
+            Some(path) => {
+                let loaded = asset_server.load_sync(textures, path)?;
+                Some(
+                    textures
+                        .remove(&loaded)
+                        .ok_or_else(|| format!("'{}' has no texture", path))?,
+                )
+            }
+            None => None,
+        };
+        let (r, g, b) = file.material.color;
+        let material = StandardMaterial {
+            albedo: Color::rgb(r, g, b),
+            albedo_texture: texture.as_ref().map(|_| texture_handle(&file.name)),
+            ..Default::default()
+        };
+
+        Ok(BuiltEntry {
+            entry: CatalogEntry {
+                id: catalog_id("entry", &file.name),
+                mesh: Handle::from_u128(catalog_id("mesh", &file.name)),
+                material: Handle::from_u128(catalog_id("material", &file.name)),
+                name: file.name,
+                key,
+                lift,
+            },
+            mesh,
+            material,
+            texture,
+        })
+    }
+
+    /// Frees the entry's mesh, material and texture
+    fn remove_assets(
+        &self,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+        textures: &mut Assets<Texture>,
+    ) {
+        meshes.remove(&self.mesh);
+        materials.remove(&self.material);
+        textures.remove(&texture_handle(&self.name));
+    }
+}
+
+impl BuiltEntry {
+    /// Stores the assets under the entry's handles, replacing the ones of a previous load
+    fn commit(
+        self,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+        textures: &mut Assets<Texture>,
+    ) -> CatalogEntry {
+        let texture = texture_handle(&self.entry.name);
+        match self.texture {
+            Some(image) => textures.set(texture, image),
+            None => {
+                textures.remove(&texture);
+            }
+        }
+        meshes.set(self.entry.mesh, self.mesh);
+        materials.set(self.entry.material, self.material);
+        self.entry
+    }
+}
+
+/// Handle of the texture of the entry with the given name, the same for each load so
+/// reloading the catalog doesn't leave old textures behind
+fn texture_handle(name: &str) -> Handle<Texture> {
+    Handle::from_u128(catalog_id("texture", name))
+}
+
+/// Entries the editor can spawn, loaded from a RON file and reloaded when it changes
+#[derive(Default)]
+pub struct SpawnCatalog {
+    pub path: String,
+    pub entries: Vec<CatalogEntry>,
+    modified: Option<SystemTime>,
+    since_check: f32,
+}
+
+impl SpawnCatalog {
+    pub fn entry(&self, id: u128) -> Option<&CatalogEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Loads the catalog from `path`, keeps the current entries and their assets if that fails.
+    /// Frees the assets of entries that were removed from the file.
+    pub fn load(
+        &mut self,
+        asset_server: &AssetServer,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+        textures: &mut Assets<Texture>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.modified = fs::metadata(&self.path)?.modified().ok();
+        let file = CatalogFile::from_ron(&load_from(self.path.clone())?)?;
+        let built = file
+            .entries
+            .into_iter()
+            .map(|entry| CatalogEntry::build(entry, asset_server, meshes, textures))
+            .collect::<Result<Vec<_>, _>>()?;
+        let entries = built
+            .into_iter()
+            .map(|built| built.commit(meshes, materials, textures))
+            .collect::<Vec<_>>();
+        for old in self.entries.iter() {
+            if !entries.iter().any(|entry| entry.id == old.id) {
+                old.remove_assets(meshes, materials, textures);
+            }
+        }
+        self.entries = entries;
+        Ok(())
+    }
+
+    /// Whether the file was modified since it was loaded, checked at most once per second
+    fn needs_reload(&mut self, delta_seconds: f32) -> bool {
+        self.since_check += delta_seconds;
+        if self.since_check < 1.0 {
+            return false;
+        }
+        self.since_check = 0.0;
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        modified.is_some() && modified != self.modified
+    }
+}
+
+pub(super) fn reload_catalog_system(
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    mut catalog: ResMut<SpawnCatalog>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut textures: ResMut<Assets<Texture>>,
+) {
+    if !catalog.needs_reload(time.delta_seconds) {
+        return;
+    }
+    match catalog.load(&asset_server, &mut meshes, &mut materials, &mut textures) {
+        Ok(_) => eprintln!("reloaded spawn catalog from {}", catalog.path),
+        Err(err) => eprintln!("failed to reload spawn catalog {}: {}", catalog.path, err),
+    }
+}
+
+/// `false` if the object's entry was removed from the catalog
+pub fn spawn_object(world: &mut World, catalog: &SpawnCatalog, state: &ObjectState) -> bool {
+    let entry = match catalog.entry(state.object.entry) {
+        Some(entry) => entry,
+        None => return false,
+    };
+    let entity = world.spawn(PbrComponents {
+        mesh: entry.mesh,
+        material: entry.material,
+        translation: Translation(state.pose.translation),
+        rotation: Rotation(state.pose.rotation),
+        scale: Scale(state.pose.scale),
+        ..Default::default()
+    });
+    world
+        .insert(entity, (Pickable::Mesh, state.object))
+        .unwrap();
+    true
+}
+
+/// Objects loaded from a persisted scene come back with their mesh, material and pose only,
+/// `EditorObject` and `Pickable` aren't registered with the type registry.
+/// Restores them from the catalog entry whose mesh the object uses, with fresh ids after
+/// `next_id`, so loaded objects can be selected, edited and undone again.
+pub fn adopt_loaded_objects(world: &mut World, catalog: &SpawnCatalog, next_id: &mut u32) {
+    let loaded = world
+        .query::<(Entity, &Handle<Mesh>, Option<&EditorObject>)>()
+        .iter()
+        .filter(|(_, _, object)| object.is_none())
+        .filter_map(|(entity, mesh, _)| {
+            catalog
+                .entries
+                .iter()
+                .find(|entry| entry.mesh == *mesh)
+                .map(|entry| (entity, entry.id))
+        })
+        .collect::<Vec<_>>();
+    for (entity, entry) in loaded {
+        *next_id += 1;
+        let object = EditorObject {
+            id: *next_id,
+            entry,
+        };
+        world.insert(entity, (Pickable::Mesh, object)).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{
+        asset::{AssetLoader, HandleId},
+        core::CorePlugin,
+        ecs::FromResources,
+        scene::SceneLoader,
+        type_registry::{TypeRegistry, TypeRegistryPlugin},
+    };
+    use std::path::Path;
+
+    #[test]
+    fn catalog_id_is_pinned() {
+        // Persisted scenes refer to entries by this id, it must never change
+        assert_eq!(
+            catalog_id("entry", "Cube"),
+            0x0a8ab0fee4601e1de7aaec1c6d44c2b6
+        );
+        assert_ne!(catalog_id("entry", "Cube"), catalog_id("mesh", "Cube"));
+        assert_ne!(catalog_id("entry", "Cube"), catalog_id("entry", "Sphere"));
+        // The separator keeps the kind and name apart
+        assert_ne!(catalog_id("entry", "Cube"), catalog_id("entryC", "ube"));
+    }
+
+    #[test]
+    fn parses_shipped_catalog() {
+        let ron = load_from("resources/scene/spawn_catalog.ron".to_string()).unwrap();
+        let file = CatalogFile::from_ron(&ron).unwrap();
+        assert_eq!(file.entries.len(), 5);
+        assert_eq!(
+            file.entries[0],
+            CatalogEntryFile {
+                name: "Cube".to_string(),
+                key: Some("Key1".to_string()),
+                mesh: MeshKind::Cube { size: 1.0 },
+                material: MaterialFile::default(),
+            }
+        );
+
+        let mut ids = Vec::new();
+        for entry in file.entries.iter() {
+            if let Some(key) = &entry.key {
+                assert!(key_code_from_str(key).is_some(), "unknown key {}", key);
+            }
+            if let MeshKind::Gltf { path } = &entry.mesh {
+                assert!(Path::new(path).exists(), "missing {}", path);
+            }
+            if let Some(path) = &entry.material.texture {
+                assert!(Path::new(path).exists(), "missing {}", path);
+            }
+            let id = catalog_id("entry", &entry.name);
+            assert!(!ids.contains(&id), "duplicate entry {}", entry.name);
+            ids.push(id);
+        }
+    }
+
+    fn cube_catalog() -> SpawnCatalog {
+        let mut catalog = SpawnCatalog::default();
+        catalog.entries.push(CatalogEntry {
+            id: catalog_id("entry", "Cube"),
+            name: "Cube".to_string(),
+            key: None,
+            mesh: Handle::from_u128(catalog_id("mesh", "Cube")),
+            material: Handle::from_u128(catalog_id("material", "Cube")),
+            lift: 0.5,
+        });
+        catalog
+    }
+
+    #[test]
+    fn persisted_objects_are_editable_after_loading() {
+        let mut app = App::build();
+        app.add_plugin(TypeRegistryPlugin::default())
+            .add_plugin(CorePlugin::default())
+            .register_property::<HandleId>()
+            .register_component::<Translation>()
+            .register_component::<Rotation>()
+            .register_component::<Scale>()
+            .register_component::<Handle<Mesh>>()
+            .register_component::<Handle<StandardMaterial>>();
+        let resources = app.resources();
+        let type_registry = resources.get::<TypeRegistry>().unwrap();
+        let catalog = cube_catalog();
+
+        let state = ObjectState {
+            object: EditorObject {
+                id: 3,
+                entry: catalog_id("entry", "Cube"),
+            },
+            pose: Pose {
+                translation: Vec3::new(1.0, 0.5, -2.0),
+                rotation: Quat::from_rotation_y(0.7),
+                scale: 1.5,
+            },
+        };
+        let mut world = World::new();
+        assert!(spawn_object(&mut world, &catalog, &state));
+        let scene = Scene::from_world(&world, &type_registry.component.read());
+        let ron = scene.serialize_ron(&type_registry.property.read()).unwrap();
+
+        // Spawn the scene the way SceneSpawner does
+        let loaded = SceneLoader::from_resources(resources)
+            .from_bytes(Path::new("scene.scn"), ron.into_bytes())
+            .unwrap();
+        let mut world = World::new();
+        for scene_entity in loaded.entities.iter() {
+            let entity = world.spawn(());
+            for component in scene_entity.components.iter() {
+                type_registry
+                    .component
+                    .read()
+                    .get_with_name(&component.type_name)
+                    .unwrap()
+                    .add_component_to_entity(&mut world, resources, entity, component);
+            }
+        }
+        let entity = world
+            .query::<(Entity, &Handle<Mesh>)>()
+            .iter()
+            .next()
+            .unwrap()
+            .0;
+        assert!(world.get::<EditorObject>(entity).is_err());
+
+        let mut next_id = 10;
+        adopt_loaded_objects(&mut world, &catalog, &mut next_id);
+        assert_eq!(next_id, 11);
+        assert_eq!(
+            *world.get::<EditorObject>(entity).unwrap(),
+            EditorObject {
+                id: 11,
+                entry: catalog_id("entry", "Cube"),
+            }
+        );
+        assert_eq!(*world.get::<Pickable>(entity).unwrap(), Pickable::Mesh);
+        let pose = Pose::new(
+            &world.get::<Translation>(entity).unwrap(),
+            &world.get::<Rotation>(entity).unwrap(),
+            &world.get::<Scale>(entity).unwrap(),
+        );
+        assert_eq!(pose, state.pose);
+        assert_eq!(
+            *world.get::<Handle<StandardMaterial>>(entity).unwrap(),
+            cube_catalog().entries[0].material
+        );
+
+        // Adopted once only
+        adopt_loaded_objects(&mut world, &catalog, &mut next_id);
+        assert_eq!(next_id, 11);
+    }
+
+    #[test]
+    fn objects_outside_the_catalog_are_left_alone() {
+        let mut world = World::new();
+        let floor = world.spawn((Handle::<Mesh>::from_u128(1),));
+        let mut next_id = 0;
+        adopt_loaded_objects(&mut world, &cube_catalog(), &mut next_id);
+        assert_eq!(next_id, 0);
+        assert!(world.get::<EditorObject>(floor).is_err());
+        assert!(world.get::<Pickable>(floor).is_err());
+    }
+}
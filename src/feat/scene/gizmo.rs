@@ -0,0 +1,501 @@
+use super::{
+    catalog::{EditorObject, Pose},
+    history::{CommandHistory, ModifyComponent},
+    EditorBindings, Selection,
+};
+use crate::libs::camera::{
+    camera_focus::FocusIgnore,
+    camera_picking::{ray_plane, CameraPickingConfig, CursorRay, Pickable, Picked, Ray},
+    camera_plugin::CameraMouseCapture,
+};
+use bevy::prelude::*;
+
+const GIZMO_MESH: Handle<Mesh> = Handle::from_u128(9876876576541120);
+const GIZMO_MATERIALS: [Handle<StandardMaterial>; 3] = [
+    Handle::from_u128(9876876576541121),
+    Handle::from_u128(9876876576541122),
+    Handle::from_u128(9876876576541123),
+];
+const GIZMO_SIZE: f32 = 0.25;
+/// Distance of the handles from the center of the selected object at scale 1
+const HANDLE_DISTANCE: f32 = 1.5;
+/// Keeps the handles of small objects apart so each one can still be grabbed
+const MIN_HANDLE_DISTANCE: f32 = GIZMO_SIZE * 2.0;
+/// Keeps the scale gizmo from flipping or collapsing the object
+const MIN_SCALE_FACTOR: f32 = 0.05;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GizmoMode {
+    Translate,
+    Rotate,
+    Scale,
+}
+
+impl Default for GizmoMode {
+    fn default() -> Self {
+        GizmoMode::Translate
+    }
+}
+
+/// Handle of the gizmo, dragging it moves the selected object along `axis`, rotates it
+/// around `axis` or scales it, depending on the `GizmoMode`
+#[derive(Clone, Copy, Debug)]
+pub struct GizmoHandle {
+    pub axis: Vec3,
+}
+
+/// Where the handle for `axis` is placed relative to the selected object with the given
+/// `scale`, so the handles stay outside of the object when it is scaled up.
+/// Rotation handles sit on the circle around their axis, otherwise they'd be at the center
+/// of the rotation and the drag angle would be undefined.
+pub fn handle_offset(mode: GizmoMode, axis: Vec3, scale: f32) -> Vec3 {
+    let distance = (HANDLE_DISTANCE * scale).max(MIN_HANDLE_DISTANCE);
+    match mode {
+        GizmoMode::Rotate => Vec3::new(axis.z(), axis.x(), axis.y()) * distance,
+        GizmoMode::Translate | GizmoMode::Scale => axis * distance,
+    }
+}
+
+/// Parameter of the point on the line through `origin` along the unit vector `axis` that is
+/// closest to the ray, `None` if the two are parallel
+pub fn closest_on_axis(ray: &Ray, origin: Vec3, axis: Vec3) -> Option<f32> {
+    let w = origin - ray.origin;
+    let b = axis.dot(ray.direction);
+    let c = ray.direction.dot(ray.direction);
+    let d = axis.dot(w);
+    let e = ray.direction.dot(w);
+    let denom = c - b * b;
+    if denom.abs() < 0.000_001 * c {
+        return None;
+    }
+    Some((b * e - c * d) / denom)
+}
+
+/// Signed angle in radians that rotates `from` onto `to` around the unit vector `axis`, both
+/// are projected onto the plane perpendicular to it
+pub fn angle_around_axis(from: Vec3, to: Vec3, axis: Vec3) -> f32 {
+    let from = from - axis * axis.dot(from);
+    let to = to - axis * axis.dot(to);
+    axis.dot(from.cross(to)).atan2(from.dot(to))
+}
+
+/// Drag of a gizmo handle that started with the object at `start`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GizmoDrag {
+    pub mode: GizmoMode,
+    pub axis: Vec3,
+    pub start: Pose,
+    /// Point on the axis (or the rotation plane) the cursor ray grabbed
+    grab: Vec3,
+}
+
+impl GizmoDrag {
+    /// `None` if the ray runs along the axis (or the rotation plane), so it can't grab it
+    pub fn begin(mode: GizmoMode, axis: Vec3, start: Pose, ray: &Ray) -> Option<Self> {
+        let grab = GizmoDrag::grab_point(mode, axis, &start, ray)?;
+        Some(GizmoDrag {
+            mode,
+            axis,
+            start,
+            grab,
+        })
+    }
+
+    fn grab_point(mode: GizmoMode, axis: Vec3, start: &Pose, ray: &Ray) -> Option<Vec3> {
+        match mode {
+            GizmoMode::Translate | GizmoMode::Scale => {
+                closest_on_axis(ray, start.translation, axis).map(|t| start.translation + axis * t)
+            }
+            GizmoMode::Rotate => ray_plane(ray, start.translation, axis).map(|hit| hit.point),
+        }
+    }
+
+    /// Pose of the object with the cursor ray at `ray`
+    pub fn pose(&self, ray: &Ray) -> Option<Pose> {
+        let point = GizmoDrag::grab_point(self.mode, self.axis, &self.start, ray)?;
+        let center = self.start.translation;
+        let mut pose = self.start;
+        match self.mode {
+            GizmoMode::Translate => pose.translation += point - self.grab,
+            GizmoMode::Rotate => {
+                let angle = angle_around_axis(self.grab - center, point - center, self.axis);
+                pose.rotation = Quat::from_axis_angle(self.axis, angle) * self.start.rotation;
+            }
+            GizmoMode::Scale => {
+                let from = (self.grab - center).dot(self.axis);
+                let to = (point - center).dot(self.axis);
+                if from.abs() > 0.000_001 {
+                    pose.scale = self.start.scale * (to / from).max(MIN_SCALE_FACTOR);
+                }
+            }
+        }
+        Some(pose)
+    }
+}
+
+#[derive(Default)]
+pub struct GizmoState {
+    pub mode: GizmoMode,
+    /// Mode of the handles that are currently spawned, `None` if there are none
+    shown: Option<GizmoMode>,
+    drag: Option<(u32, GizmoDrag)>,
+    picked_events: EventReader<Picked>,
+}
+
+pub(super) fn init_gizmo(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    meshes.set(GIZMO_MESH, Mesh::from(shape::Cube { size: GIZMO_SIZE }));
+    let colors = [
+        Color::rgb(0.9, 0.2, 0.2),
+        Color::rgb(0.2, 0.9, 0.2),
+        Color::rgb(0.2, 0.2, 0.9),
+    ];
+    for (material, albedo) in GIZMO_MATERIALS.iter().zip(colors.iter()) {
+        materials.set(
+            *material,
+            StandardMaterial {
+                albedo: *albedo,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+pub(super) fn gizmo_mode_system(
+    bindings: Res<EditorBindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut state: ResMut<GizmoState>,
+) {
+    let modes = [
+        (bindings.translate, GizmoMode::Translate),
+        (bindings.rotate, GizmoMode::Rotate),
+        (bindings.scale, GizmoMode::Scale),
+    ];
+    for (key, mode) in modes.iter() {
+        if keyboard_input.just_pressed(*key) {
+            state.mode = *mode;
+        }
+    }
+}
+
+/// Spawns the handles around the selected object and keeps them there
+pub(super) fn gizmo_handles_system(
+    mut commands: Commands,
+    selection: Res<Selection>,
+    mut state: ResMut<GizmoState>,
+    mut object_query: Query<(&EditorObject, &Translation, &Scale)>,
+    mut handle_query: Query<(Entity, &GizmoHandle, &mut Translation)>,
+) {
+    let mut selected = None;
+    for (object, translation, scale) in &mut object_query.iter() {
+        if selection.id == Some(object.id) {
+            selected = Some((translation.0, scale.0));
+        }
+    }
+
+    let shown = selected.map(|_| state.mode);
+    if shown != state.shown {
+        for (entity, _, _) in &mut handle_query.iter() {
+            commands.despawn(entity);
+        }
+        if let Some((center, scale)) = selected {
+            let axes = [Vec3::unit_x(), Vec3::unit_y(), Vec3::unit_z()];
+            for (axis, material) in axes.iter().zip(GIZMO_MATERIALS.iter()) {
+                commands
+                    .spawn(PbrComponents {
+                        mesh: GIZMO_MESH,
+                        material: *material,
+                        translation: Translation(center + handle_offset(state.mode, *axis, scale)),
+                        ..Default::default()
+                    })
+                    .with(GizmoHandle { axis: *axis })
+                    .with(FocusIgnore)
+                    // Twice the size of the handle to make it easier to grab
+                    .with(Pickable::Box {
+                        half_extents: Vec3::splat(GIZMO_SIZE),
+                        offset: Vec3::zero(),
+                    });
+            }
+        }
+        state.shown = shown;
+        return;
+    }
+
+    if let Some((center, scale)) = selected {
+        for (_, handle, mut translation) in &mut handle_query.iter() {
+            translation.0 = center + handle_offset(state.mode, handle.axis, scale);
+        }
+    }
+}
+
+/// Despawns the handles, i.e. so they aren't saved with the scene.
+/// `gizmo_handles_system` spawns them again on its next run.
+pub fn despawn_gizmo(world: &mut World, resources: &Resources) {
+    let handles = world
+        .query::<(Entity, &GizmoHandle)>()
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    for entity in handles {
+        world.despawn(entity).unwrap();
+    }
+    if let Some(mut state) = resources.get_mut::<GizmoState>() {
+        state.shown = None;
+    }
+}
+
+/// Drags the selected object with a gizmo handle and records the change once it's released
+pub(super) fn gizmo_drag_system(
+    picking: Res<CameraPickingConfig>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    cursor_ray: Res<CursorRay>,
+    picked_events: Res<Events<Picked>>,
+    selection: Res<Selection>,
+    mut state: ResMut<GizmoState>,
+    mut mouse_capture: ResMut<CameraMouseCapture>,
+    mut history: ResMut<CommandHistory>,
+    handle_query: Query<&GizmoHandle>,
+    mut object_query: Query<(&EditorObject, &mut Translation, &mut Rotation, &mut Scale)>,
+) {
+    let mut grabbed = None;
+    for picked in state.picked_events.iter(&picked_events) {
+        if let Ok(handle) = handle_query.get::<GizmoHandle>(picked.entity) {
+            grabbed = Some(handle.axis);
+        }
+    }
+    if let (Some(axis), Some(id), Some(ray)) = (grabbed, selection.id, cursor_ray.0) {
+        for (object, translation, rotation, scale) in &mut object_query.iter() {
+            if object.id == id {
+                let start = Pose::new(&translation, &rotation, &scale);
+                state.drag = GizmoDrag::begin(state.mode, axis, start, &ray).map(|drag| (id, drag));
+            }
+        }
+        mouse_capture.captured = state.drag.is_some();
+    }
+
+    let (id, drag) = match state.drag {
+        Some(drag) => drag,
+        None => return,
+    };
+    let mut pose = None;
+    for (object, mut translation, mut rotation, mut scale) in &mut object_query.iter() {
+        if object.id != id {
+            continue;
+        }
+        if let Some(dragged) = cursor_ray.0.and_then(|ray| drag.pose(&ray)) {
+            dragged.write(&mut translation, &mut rotation, &mut scale);
+        }
+        pose = Some(Pose::new(&translation, &rotation, &scale));
+    }
+    if mouse_button_input.pressed(picking.button) && pose.is_some() {
+        return;
+    }
+
+    // Released, or the object was deleted while it was dragged
+    match pose {
+        Some(pose) if pose != drag.start => {
+            let (from, to) = (drag.start, pose);
+            match drag.mode {
+                GizmoMode::Translate => history.execute(ModifyComponent::new(
+                    id,
+                    Translation(from.translation),
+                    Translation(to.translation),
+                )),
+                GizmoMode::Rotate => history.execute(ModifyComponent::new(
+                    id,
+                    Rotation(from.rotation),
+                    Rotation(to.rotation),
+                )),
+                GizmoMode::Scale => {
+                    history.execute(ModifyComponent::new(id, Scale(from.scale), Scale(to.scale)))
+                }
+            }
+        }
+        _ => {}
+    }
+    state.drag = None;
+    mouse_capture.captured = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual - expected).length() < 0.0001,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    /// Ray straight down the -z axis through `x` and `y`
+    fn ray_at(x: f32, y: f32) -> Ray {
+        Ray {
+            origin: Vec3::new(x, y, 5.0),
+            direction: -Vec3::unit_z(),
+        }
+    }
+
+    fn start() -> Pose {
+        Pose {
+            translation: Vec3::new(1.0, 0.0, 0.0),
+            rotation: Quat::from_rotation_z(0.3),
+            scale: 2.0,
+        }
+    }
+
+    #[test]
+    fn closest_on_axis_projects_the_ray_onto_the_axis() {
+        let t = closest_on_axis(&ray_at(3.0, 2.0), Vec3::zero(), Vec3::unit_x()).unwrap();
+        assert!((t - 3.0).abs() < 0.0001, "{}", t);
+        // Relative to the origin of the axis
+        let t = closest_on_axis(&ray_at(3.0, 2.0), Vec3::new(1.0, 1.0, 0.0), Vec3::unit_x());
+        assert!((t.unwrap() - 2.0).abs() < 0.0001);
+        // Oblique ray, the closest point is where it passes over the axis
+        let ray = Ray {
+            origin: Vec3::new(0.0, 4.0, 4.0),
+            direction: Vec3::new(1.0, -1.0, -1.0),
+        };
+        let t = closest_on_axis(&ray, Vec3::zero(), Vec3::unit_x()).unwrap();
+        assert!((t - 4.0).abs() < 0.0001, "{}", t);
+    }
+
+    #[test]
+    fn closest_on_axis_rejects_parallel_rays() {
+        let ray = Ray {
+            origin: Vec3::new(0.0, 1.0, 0.0),
+            direction: Vec3::unit_x(),
+        };
+        assert_eq!(closest_on_axis(&ray, Vec3::zero(), Vec3::unit_x()), None);
+        assert_eq!(closest_on_axis(&ray, Vec3::zero(), -Vec3::unit_x()), None);
+    }
+
+    #[test]
+    fn angle_around_axis_is_signed() {
+        let y = Vec3::unit_y();
+        let angle = angle_around_axis(Vec3::unit_x(), -Vec3::unit_z(), y);
+        assert!((angle - FRAC_PI_2).abs() < 0.0001, "{}", angle);
+        assert_near(
+            Quat::from_axis_angle(y, angle) * Vec3::unit_x(),
+            -Vec3::unit_z(),
+        );
+
+        let angle = angle_around_axis(Vec3::unit_x(), Vec3::unit_z(), y);
+        assert!((angle + FRAC_PI_2).abs() < 0.0001, "{}", angle);
+        assert!(angle_around_axis(Vec3::unit_x(), Vec3::unit_x(), y).abs() < 0.0001);
+    }
+
+    #[test]
+    fn angle_around_axis_ignores_the_axis_component() {
+        let angle = angle_around_axis(
+            Vec3::new(1.0, 5.0, 0.0),
+            Vec3::new(0.0, -3.0, -2.0),
+            Vec3::unit_y(),
+        );
+        assert!((angle - FRAC_PI_2).abs() < 0.0001, "{}", angle);
+    }
+
+    #[test]
+    fn translate_drag_moves_along_the_axis() {
+        let drag = GizmoDrag::begin(
+            GizmoMode::Translate,
+            Vec3::unit_x(),
+            start(),
+            &ray_at(2.0, 0.0),
+        )
+        .unwrap();
+        // Moving the cursor off the axis only counts along it
+        let pose = drag.pose(&ray_at(4.0, 3.0)).unwrap();
+        assert_near(pose.translation, Vec3::new(3.0, 0.0, 0.0));
+        assert_eq!(pose.rotation, start().rotation);
+        assert_eq!(pose.scale, start().scale);
+        // Back where it was grabbed
+        assert_eq!(drag.pose(&ray_at(2.0, 0.0)), Some(start()));
+    }
+
+    #[test]
+    fn drag_cant_begin_along_the_axis() {
+        assert_eq!(
+            GizmoDrag::begin(
+                GizmoMode::Translate,
+                Vec3::unit_z(),
+                start(),
+                &ray_at(1.0, 0.0)
+            ),
+            None
+        );
+        // The rotation plane is edge on
+        assert_eq!(
+            GizmoDrag::begin(
+                GizmoMode::Rotate,
+                Vec3::unit_x(),
+                start(),
+                &ray_at(1.0, 1.0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn rotate_drag_turns_around_the_axis() {
+        let down = |x, z| Ray {
+            origin: Vec3::new(x, 5.0, z),
+            direction: -Vec3::unit_y(),
+        };
+        let start = Pose::at(Vec3::zero());
+        let drag =
+            GizmoDrag::begin(GizmoMode::Rotate, Vec3::unit_y(), start, &down(2.0, 0.0)).unwrap();
+        let pose = drag.pose(&down(0.0, -2.0)).unwrap();
+        assert_near(pose.rotation * Vec3::unit_x(), -Vec3::unit_z());
+        assert_near(pose.translation, Vec3::zero());
+        assert_eq!(pose.scale, 1.0);
+    }
+
+    #[test]
+    fn scale_drag_scales_by_distance_from_the_center() {
+        let start = Pose {
+            translation: Vec3::zero(),
+            ..start()
+        };
+        let drag =
+            GizmoDrag::begin(GizmoMode::Scale, Vec3::unit_x(), start, &ray_at(1.0, 0.0)).unwrap();
+        assert!((drag.pose(&ray_at(3.0, 0.0)).unwrap().scale - 6.0).abs() < 0.0001);
+        assert!((drag.pose(&ray_at(0.5, 0.0)).unwrap().scale - 1.0).abs() < 0.0001);
+        // Dragging through the center doesn't flip the object
+        let scale = drag.pose(&ray_at(-1.0, 0.0)).unwrap().scale;
+        assert!((scale - 2.0 * MIN_SCALE_FACTOR).abs() < 0.0001, "{}", scale);
+    }
+
+    #[test]
+    fn despawn_gizmo_removes_handles_only() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(GizmoState {
+            shown: Some(GizmoMode::Translate),
+            ..Default::default()
+        });
+        let object = world.spawn((Translation::default(),));
+        world.spawn((
+            GizmoHandle {
+                axis: Vec3::unit_x(),
+            },
+            Translation::default(),
+        ));
+        world.spawn((
+            GizmoHandle {
+                axis: Vec3::unit_y(),
+            },
+            Translation::default(),
+        ));
+
+        despawn_gizmo(&mut world, &resources);
+        assert_eq!(world.query::<&GizmoHandle>().iter().count(), 0);
+        assert!(world.get::<Translation>(object).is_ok());
+        assert_eq!(resources.get::<GizmoState>().unwrap().shown, None);
+    }
+}
@@ -0,0 +1,325 @@
+use super::{
+    catalog::{spawn_object, EditorObject, ObjectState, SpawnCatalog},
+    EditorBindings, Selection,
+};
+use bevy::{ecs::Component, prelude::*};
+use std::collections::VecDeque;
+
+/// A scene edit that knows how to revert itself.
+/// Commands find their objects via `EditorObject::id`, since the entity of an object changes
+/// when it is despawned and spawned again.
+pub trait SceneCommand: Send + Sync {
+    fn apply(&mut self, world: &mut World, resources: &Resources);
+    fn undo(&mut self, world: &mut World, resources: &Resources);
+}
+
+/// Entity of the object with the given id, `None` if it isn't spawned
+pub fn find_object(world: &World, id: u32) -> Option<Entity> {
+    world
+        .query::<(Entity, &EditorObject)>()
+        .iter()
+        .find(|(_, object)| object.id == id)
+        .map(|(entity, _)| entity)
+}
+
+fn despawn_object(world: &mut World, resources: &Resources, id: u32) {
+    if let Some(entity) = find_object(world, id) {
+        world.despawn(entity).unwrap();
+    }
+    if let Some(mut selection) = resources.get_mut::<Selection>() {
+        if selection.id == Some(id) {
+            selection.id = None;
+        }
+    }
+}
+
+fn respawn_object(world: &mut World, resources: &Resources, state: &ObjectState) {
+    let catalog = resources
+        .get::<SpawnCatalog>()
+        .expect("SpawnPlugin should have been added");
+    if !spawn_object(world, &catalog, state) {
+        eprintln!(
+            "can't restore object {}, its catalog entry was removed",
+            state.object.id
+        );
+    }
+}
+
+pub struct SpawnObject(pub ObjectState);
+
+impl SceneCommand for SpawnObject {
+    fn apply(&mut self, world: &mut World, resources: &Resources) {
+        respawn_object(world, resources, &self.0);
+    }
+    fn undo(&mut self, world: &mut World, resources: &Resources) {
+        despawn_object(world, resources, self.0.object.id);
+    }
+}
+
+/// Despawns the object, `ObjectState::pose` is where it is restored on undo
+pub struct DespawnObject(pub ObjectState);
+
+impl SceneCommand for DespawnObject {
+    fn apply(&mut self, world: &mut World, resources: &Resources) {
+        despawn_object(world, resources, self.0.object.id);
+    }
+    fn undo(&mut self, world: &mut World, resources: &Resources) {
+        respawn_object(world, resources, &self.0);
+    }
+}
+
+/// Sets a component of an object from `from` to `to`, i.e. its `Translation`
+pub struct ModifyComponent<T> {
+    pub id: u32,
+    pub from: T,
+    pub to: T,
+}
+
+impl<T: Component + Clone> ModifyComponent<T> {
+    pub fn new(id: u32, from: T, to: T) -> Self {
+        ModifyComponent { id, from, to }
+    }
+
+    fn set(&self, world: &mut World, value: &T) {
+        if let Some(entity) = find_object(world, self.id) {
+            if let Ok(mut component) = world.get_mut::<T>(entity) {
+                *component = value.clone();
+            }
+        }
+    }
+}
+
+impl<T: Component + Clone> SceneCommand for ModifyComponent<T> {
+    fn apply(&mut self, world: &mut World, _resources: &Resources) {
+        self.set(world, &self.to);
+    }
+    fn undo(&mut self, world: &mut World, _resources: &Resources) {
+        self.set(world, &self.from);
+    }
+}
+
+/// Commands applied to the scene, bounded by `capacity`.
+/// Systems queue commands via `execute`, they're applied by `history_system` which also
+/// handles undo and redo.
+pub struct CommandHistory {
+    /// Oldest commands are forgotten once there are more than this
+    pub capacity: usize,
+    pending: Vec<Box<dyn SceneCommand>>,
+    undo: VecDeque<Box<dyn SceneCommand>>,
+    redo: Vec<Box<dyn SceneCommand>>,
+}
+
+impl Default for CommandHistory {
+    fn default() -> Self {
+        CommandHistory::with_capacity(100)
+    }
+}
+
+impl CommandHistory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        CommandHistory {
+            capacity,
+            pending: Vec::new(),
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Queues the command to be applied and recorded
+    pub fn execute(&mut self, command: impl SceneCommand + 'static) {
+        self.pending.push(Box::new(command));
+    }
+
+    /// Applies the queued commands, commands that were undone can't be redone after this
+    pub fn apply_pending(&mut self, world: &mut World, resources: &Resources) {
+        for mut command in self.pending.drain(..) {
+            command.apply(world, resources);
+            self.undo.push_back(command);
+            self.redo.clear();
+        }
+        while self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Reverts the most recent command, `false` if there is none
+    pub fn undo(&mut self, world: &mut World, resources: &Resources) -> bool {
+        match self.undo.pop_back() {
+            Some(mut command) => {
+                command.undo(world, resources);
+                self.redo.push(command);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Applies the most recently undone command again, `false` if there is none
+    pub fn redo(&mut self, world: &mut World, resources: &Resources) -> bool {
+        match self.redo.pop() {
+            Some(mut command) => {
+                command.apply(world, resources);
+                self.undo.push_back(command);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Needs exclusive access to the world in order to spawn and despawn right away, so the
+/// objects of queued commands exist when the next command or undo looks for them
+pub(super) fn history_system(world: &mut World, resources: &mut Resources) {
+    let mut history = resources.get_mut::<CommandHistory>().unwrap();
+    history.apply_pending(world, resources);
+
+    let keyboard_input = resources.get::<Input<KeyCode>>().unwrap();
+    let bindings = resources.get::<EditorBindings>().unwrap();
+    if !keyboard_input.pressed(bindings.modifier) {
+        return;
+    }
+    if keyboard_input.just_pressed(bindings.undo) {
+        history.undo(world, resources);
+    }
+    if keyboard_input.just_pressed(bindings.redo) {
+        history.redo(world, resources);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feat::scene::catalog::{CatalogEntry, Pose};
+
+    const ENTRY: u128 = 42;
+
+    fn headless() -> (World, Resources) {
+        let mut catalog = SpawnCatalog::default();
+        catalog.entries.push(CatalogEntry {
+            id: ENTRY,
+            name: "Cube".to_string(),
+            key: None,
+            mesh: Handle::from_u128(1),
+            material: Handle::from_u128(2),
+            lift: 0.5,
+        });
+        let mut resources = Resources::default();
+        resources.insert(catalog);
+        resources.insert(Selection::default());
+        (World::new(), resources)
+    }
+
+    fn object(id: u32, translation: Vec3) -> ObjectState {
+        ObjectState {
+            object: EditorObject { id, entry: ENTRY },
+            pose: Pose {
+                translation,
+                rotation: Quat::from_rotation_y(0.5),
+                scale: 2.0,
+            },
+        }
+    }
+
+    fn pose_of(world: &World, id: u32) -> Option<Pose> {
+        let entity = find_object(world, id)?;
+        Some(Pose::new(
+            &world.get::<Translation>(entity).unwrap(),
+            &world.get::<Rotation>(entity).unwrap(),
+            &world.get::<Scale>(entity).unwrap(),
+        ))
+    }
+
+    #[test]
+    fn spawn_undo_redo() {
+        let (mut world, resources) = headless();
+        let mut history = CommandHistory::default();
+        let state = object(1, Vec3::new(1.0, 0.5, 2.0));
+
+        history.execute(SpawnObject(state));
+        assert_eq!(pose_of(&world, 1), None, "commands wait for apply_pending");
+        history.apply_pending(&mut world, &resources);
+        assert_eq!(pose_of(&world, 1), Some(state.pose));
+
+        assert!(history.undo(&mut world, &resources));
+        assert_eq!(pose_of(&world, 1), None);
+        assert!(!history.undo(&mut world, &resources));
+
+        assert!(history.redo(&mut world, &resources));
+        assert_eq!(pose_of(&world, 1), Some(state.pose));
+        assert!(!history.redo(&mut world, &resources));
+    }
+
+    #[test]
+    fn undo_despawn_restores_pose_and_id() {
+        let (mut world, resources) = headless();
+        let mut history = CommandHistory::default();
+        let state = object(7, Vec3::new(-3.0, 0.5, 4.0));
+        history.execute(SpawnObject(state));
+        history.apply_pending(&mut world, &resources);
+        let spawned = find_object(&world, 7).unwrap();
+        resources.get_mut::<Selection>().unwrap().id = Some(7);
+
+        history.execute(DespawnObject(state));
+        history.apply_pending(&mut world, &resources);
+        assert_eq!(find_object(&world, 7), None);
+        assert!(!world.contains(spawned));
+        assert_eq!(resources.get::<Selection>().unwrap().id, None);
+
+        assert!(history.undo(&mut world, &resources));
+        assert_eq!(pose_of(&world, 7), Some(state.pose));
+        let restored = find_object(&world, 7).unwrap();
+        assert_eq!(*world.get::<EditorObject>(restored).unwrap(), state.object);
+    }
+
+    #[test]
+    fn undo_modify_component() {
+        let (mut world, resources) = headless();
+        let mut history = CommandHistory::default();
+        let from = Vec3::new(0.0, 0.5, 0.0);
+        let to = Vec3::new(5.0, 0.5, -1.0);
+        history.execute(SpawnObject(object(3, from)));
+        history.execute(ModifyComponent::new(3, Translation(from), Translation(to)));
+        history.apply_pending(&mut world, &resources);
+        assert_eq!(pose_of(&world, 3).unwrap().translation, to);
+
+        assert!(history.undo(&mut world, &resources));
+        assert_eq!(pose_of(&world, 3).unwrap().translation, from);
+        // Only the translation was modified
+        assert_eq!(pose_of(&world, 3).unwrap().scale, 2.0);
+
+        assert!(history.redo(&mut world, &resources));
+        assert_eq!(pose_of(&world, 3).unwrap().translation, to);
+    }
+
+    #[test]
+    fn capacity_forgets_oldest_commands() {
+        let (mut world, resources) = headless();
+        let mut history = CommandHistory::with_capacity(2);
+        for id in 1..=3 {
+            history.execute(SpawnObject(object(id, Vec3::zero())));
+        }
+        history.apply_pending(&mut world, &resources);
+
+        assert!(history.undo(&mut world, &resources));
+        assert!(history.undo(&mut world, &resources));
+        assert!(!history.undo(&mut world, &resources));
+        assert!(find_object(&world, 1).is_some());
+        assert_eq!(find_object(&world, 2), None);
+        assert_eq!(find_object(&world, 3), None);
+    }
+
+    #[test]
+    fn new_command_clears_redo() {
+        let (mut world, resources) = headless();
+        let mut history = CommandHistory::default();
+        history.execute(SpawnObject(object(1, Vec3::zero())));
+        history.apply_pending(&mut world, &resources);
+        assert!(history.undo(&mut world, &resources));
+
+        history.execute(SpawnObject(object(2, Vec3::unit_x())));
+        history.apply_pending(&mut world, &resources);
+        assert!(!history.redo(&mut world, &resources));
+        assert_eq!(find_object(&world, 1), None);
+        assert!(find_object(&world, 2).is_some());
+    }
+}
@@ -1,85 +1,227 @@
+//
+// Small scene editor: spawn entries of the spawn catalog where the cursor points at the floor,
+// click objects to select them and drag the gizmo handles to move, rotate or scale them.
+// Requires a camera with picking enabled, see `AddCameraOpts::picking`.
+//
+
+pub mod catalog;
+pub mod gizmo;
+pub mod history;
+
+use crate::libs::camera::camera_picking::{ray_plane, CursorRay, Picked};
 use bevy::prelude::*;
+use catalog::{
+    adopt_loaded_objects, reload_catalog_system, EditorObject, ObjectState, Pose, SpawnCatalog,
+};
+use gizmo::{gizmo_drag_system, gizmo_handles_system, gizmo_mode_system, init_gizmo, GizmoState};
+use history::{history_system, CommandHistory, DespawnObject, SpawnObject};
 
-enum SpawnRequest {
-    Cube,
-    Sphere,
+#[derive(Clone, Copy)]
+pub struct EditorBindings {
+    pub translate: KeyCode,
+    pub rotate: KeyCode,
+    pub scale: KeyCode,
+    pub delete: KeyCode,
+    /// Spawns a copy of the selected object next to it
+    pub duplicate: KeyCode,
+    /// Not Escape, which releases the grabbed cursor, see `CameraBindings::release_cursor`
+    pub deselect: KeyCode,
+    /// Undoes the last edit while `modifier` is held
+    pub undo: KeyCode,
+    /// Redoes the last undone edit while `modifier` is held
+    pub redo: KeyCode,
+    /// Needs to be held for undo and redo. Left Control by default, which is also the camera's
+    /// `CameraBindings::crawl`, so the camera slows down while it is held. Neither undo nor redo
+    /// moves the camera though.
+    pub modifier: KeyCode,
 }
 
-const CUBE_MESH: Handle<Mesh> = Handle::from_u128(9876876576541110);
-const CUBE_MATERIAL: Handle<StandardMaterial> = Handle::from_u128(9876876576541111);
-const SPHERE_MESH: Handle<Mesh> = Handle::from_u128(9876876576541112);
-const SPHERE_MATERIAL: Handle<StandardMaterial> = Handle::from_u128(9876876576541113);
+impl Default for EditorBindings {
+    fn default() -> Self {
+        EditorBindings {
+            translate: KeyCode::G,
+            rotate: KeyCode::R,
+            scale: KeyCode::T,
+            delete: KeyCode::Delete,
+            duplicate: KeyCode::V,
+            deselect: KeyCode::X,
+            undo: KeyCode::Z,
+            redo: KeyCode::Y,
+            modifier: KeyCode::LControl,
+        }
+    }
+}
+
+/// Id of the selected `EditorObject`
+#[derive(Default)]
+pub struct Selection {
+    pub id: Option<u32>,
+}
 
 #[derive(Default)]
 struct SpawnState {
-    spawn_request: Option<SpawnRequest>,
-    translation: Translation,
+    next_id: u32,
+    picked_events: EventReader<Picked>,
 }
 
-pub struct SpawnPlugin;
+impl SpawnState {
+    fn next_object(&mut self, entry: u128, pose: Pose) -> ObjectState {
+        self.next_id += 1;
+        ObjectState {
+            object: EditorObject {
+                id: self.next_id,
+                entry,
+            },
+            pose,
+        }
+    }
+}
+
+pub struct SpawnPlugin {
+    /// RON file listing what can be spawned, see `catalog::CatalogFile`.
+    /// It is reloaded when it changes while the app is running.
+    pub catalog: String,
+}
+
+impl Default for SpawnPlugin {
+    fn default() -> Self {
+        SpawnPlugin {
+            catalog: "resources/scene/spawn_catalog.ron".to_string(),
+        }
+    }
+}
 
 impl Plugin for SpawnPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.add_resource(SpawnState::default())
+        let catalog = SpawnCatalog {
+            path: self.catalog.clone(),
+            ..Default::default()
+        };
+        app.init_resource::<SpawnState>()
+            .add_resource(catalog)
+            .init_resource::<Selection>()
+            .init_resource::<CommandHistory>()
+            .init_resource::<GizmoState>()
+            .add_resource(EditorBindings::default())
             .add_startup_system(init_plugin.system())
-            .add_system(keyboard_commands.system())
-            .add_system(update_scene.system());
+            .add_startup_system(init_gizmo.system())
+            .add_system(reload_catalog_system.system())
+            .add_system(spawn_at_cursor.system())
+            .add_system(select_picked.system())
+            .add_system(edit_selected.system())
+            .add_system(gizmo_mode_system.system())
+            .add_system(gizmo_drag_system.system())
+            .add_system(gizmo_handles_system.system())
+            .add_system(adopt_loaded_system.thread_local_system())
+            .add_system(history_system.thread_local_system());
     }
 }
 
-fn init_plugin(mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<StandardMaterial>>) {
-    meshes.set(CUBE_MESH, Mesh::from(shape::Cube { size: 1.0 }));
-    materials.set(
-        CUBE_MATERIAL,
-        StandardMaterial {
-            albedo: Color::rgb(0.5, 0.4, 0.3),
-            ..Default::default()
-        },
-    );
-    meshes.set(
-        SPHERE_MESH,
-        Mesh::from(shape::Icosphere {
-            subdivisions: 4,
-            radius: 1.0,
-        }),
-    );
-    materials.set(
-        SPHERE_MATERIAL,
-        StandardMaterial {
-            albedo: Color::rgb(0.5, 0.4, 0.3),
-            ..Default::default()
-        },
-    );
+fn init_plugin(
+    asset_server: Res<AssetServer>,
+    mut catalog: ResMut<SpawnCatalog>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut textures: ResMut<Assets<Texture>>,
+) {
+    catalog
+        .load(&asset_server, &mut meshes, &mut materials, &mut textures)
+        .expect("Failed to load spawn catalog");
+    for entry in catalog.entries.iter() {
+        if let Some(key) = entry.key {
+            eprintln!("Press '{:?}' to spawn a {}", key, entry.name);
+        }
+    }
 }
 
-fn keyboard_commands(mut state: ResMut<SpawnState>, keyboard_input: Res<Input<KeyCode>>) {
-    if keyboard_input.just_pressed(KeyCode::Key1) {
-        state.spawn_request = Some(SpawnRequest::Cube);
+/// Makes objects of a loaded scene editable, see `catalog::adopt_loaded_objects`
+fn adopt_loaded_system(world: &mut World, resources: &mut Resources) {
+    let catalog = resources.get::<SpawnCatalog>().unwrap();
+    let mut state = resources.get_mut::<SpawnState>().unwrap();
+    adopt_loaded_objects(world, &catalog, &mut state.next_id);
+}
+
+fn spawn_at_cursor(
+    keyboard_input: Res<Input<KeyCode>>,
+    cursor_ray: Res<CursorRay>,
+    catalog: Res<SpawnCatalog>,
+    mut state: ResMut<SpawnState>,
+    mut selection: ResMut<Selection>,
+    mut history: ResMut<CommandHistory>,
+) {
+    // Leaves Alt and a number key to camera bookmarks, see `CameraBookmarksConfig::modifier`
+    if keyboard_input.pressed(KeyCode::LAlt) || keyboard_input.pressed(KeyCode::RAlt) {
+        return;
     }
-    if keyboard_input.just_pressed(KeyCode::Key2) {
-        state.spawn_request = Some(SpawnRequest::Sphere);
+    for entry in catalog.entries.iter() {
+        match entry.key {
+            Some(key) if keyboard_input.just_pressed(key) => {}
+            _ => continue,
+        }
+        // Spawn at the center of the floor if the cursor doesn't point at it
+        let floor = cursor_ray
+            .0
+            .and_then(|ray| ray_plane(&ray, Vec3::zero(), Vec3::unit_y()))
+            .map_or(Vec3::zero(), |hit| hit.point);
+        let object = state.next_object(entry.id, Pose::at(floor + Vec3::unit_y() * entry.lift));
+        history.execute(SpawnObject(object));
+        selection.id = Some(object.object.id);
     }
 }
 
-fn update_scene(mut commands: Commands, mut state: ResMut<SpawnState>) {
-    match &state.spawn_request {
-        Some(request) => {
-            let (mesh, material) = match request {
-                SpawnRequest::Cube => (CUBE_MESH, CUBE_MATERIAL),
-                SpawnRequest::Sphere => (SPHERE_MESH, SPHERE_MATERIAL),
-            };
-
-            state.translation.0 += Vec3::new(0.0, 2.0, 0.0);
-
-            commands.spawn(PbrComponents {
-                mesh,
-                material,
-                translation: state.translation,
-                ..Default::default()
-            });
+fn select_picked(
+    bindings: Res<EditorBindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    picked_events: Res<Events<Picked>>,
+    mut state: ResMut<SpawnState>,
+    mut selection: ResMut<Selection>,
+    object_query: Query<&EditorObject>,
+) {
+    for picked in state.picked_events.iter(&picked_events) {
+        // Picking a gizmo handle keeps the selection
+        if let Ok(object) = object_query.get::<EditorObject>(picked.entity) {
+            selection.id = Some(object.id);
+        }
+    }
+    if keyboard_input.just_pressed(bindings.deselect) {
+        selection.id = None;
+    }
+}
 
-            state.spawn_request = None;
+fn edit_selected(
+    bindings: Res<EditorBindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut state: ResMut<SpawnState>,
+    mut selection: ResMut<Selection>,
+    mut history: ResMut<CommandHistory>,
+    mut object_query: Query<(&EditorObject, &Translation, &Rotation, &Scale)>,
+) {
+    let delete = keyboard_input.just_pressed(bindings.delete);
+    let duplicate = keyboard_input.just_pressed(bindings.duplicate);
+    if !delete && !duplicate {
+        return;
+    }
+    let mut selected = None;
+    for (object, translation, rotation, scale) in &mut object_query.iter() {
+        if selection.id == Some(object.id) {
+            selected = Some(ObjectState {
+                object: *object,
+                pose: Pose::new(&translation, &rotation, &scale),
+            });
         }
-        None => {}
+    }
+    let selected = match selected {
+        Some(selected) => selected,
+        None => return,
+    };
+
+    if delete {
+        history.execute(DespawnObject(selected));
+    } else {
+        let mut pose = selected.pose;
+        pose.translation += Vec3::unit_x() * (pose.scale + 0.5);
+        let copy = state.next_object(selected.object.entry, pose);
+        history.execute(SpawnObject(copy));
+        selection.id = Some(copy.object.id);
     }
 }
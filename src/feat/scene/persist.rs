@@ -2,12 +2,13 @@ use std::{error::Error, fs};
 
 use bevy::{prelude::*, type_registry::TypeRegistry};
 use bevy_gl::{
-    feat::scene::SpawnPlugin,
+    feat::scene::{gizmo::despawn_gizmo, SpawnPlugin},
     libs::util::init_tmp_path,
     libs::util::save_to_tmp,
     libs::{
         app::app_default,
         camera::{
+            camera_bookmarks::CameraBookmarksConfig,
             camera_info::CameraInfoConfig,
             camera_plugin::{AddCameraOpts, CameraTrait},
             camera_view::CameraViewOpts,
@@ -41,10 +42,19 @@ fn main() {
                 yaw: -136.00,
                 ..Default::default()
             },
+            // The SpawnPlugin editor places and selects objects via the cursor ray
+            picking: Some(Default::default()),
+            // Number keys by themselves spawn catalog entries
+            bookmarks: Some(CameraBookmarksConfig {
+                feat_id: "feat_scene_persist",
+                modifier: Some(KeyCode::LAlt),
+                ..Default::default()
+            }),
+            ..Default::default()
         })
         .add_system(keyboard_commands.system())
         .add_resource(RequestState::default())
-        .add_plugin(SpawnPlugin {})
+        .add_plugin(SpawnPlugin::default())
         .run();
 }
 
@@ -99,6 +109,8 @@ fn handle_persist_request(world: &mut World, resources: &mut Resources) {
 
     match request_state.requested {
         Some(Request::PersistScene) => {
+            // Handles are editor state, they'd come back as loose cubes
+            despawn_gizmo(world, resources);
             let type_registry = resources.get::<TypeRegistry>().unwrap();
             let scene = Scene::from_world(&world, &type_registry.component.read());
 
@@ -4,7 +4,9 @@ use bevy_gl::{
     libs::{
         app::app_default,
         camera::{
+            camera_focus::FocusIgnore,
             camera_info::CameraInfoConfig,
+            camera_picking::Picked,
             camera_plugin::{AddCameraOpts, CameraTrait},
             camera_view::CameraViewOpts,
         },
@@ -13,8 +15,10 @@ use bevy_gl::{
 
 fn main() {
     app_default("Scene Save/Reload".to_string())
-        .add_plugin(SpawnPlugin {})
+        .add_plugin(SpawnPlugin::default())
         .add_startup_system(setup.system())
+        .init_resource::<PickedEvents>()
+        .add_system(log_picked.system())
         .add_camera_from(AddCameraOpts {
             info: Some(CameraInfoConfig::default()),
             position: (12.24, 8.03, 11.26).into(),
@@ -23,10 +27,29 @@ fn main() {
                 yaw: -136.00,
                 ..Default::default()
             },
+            picking: Some(Default::default()),
+            ..Default::default()
         })
         .run();
 
-    eprintln!("Press '1' to spawn a Cube, '2' to spawn a Sphere");
+    eprintln!("Click an object to select it, 'G', 'R' and 'T' to translate, rotate or scale it");
+    eprintln!(
+        "'V' duplicates, 'Delete' deletes, 'Ctrl+Z'/'Ctrl+Y' undo/redo, 'O' for orthographic"
+    );
+}
+
+#[derive(Default)]
+struct PickedEvents {
+    reader: EventReader<Picked>,
+}
+
+fn log_picked(mut picked: ResMut<PickedEvents>, picked_events: Res<Events<Picked>>) {
+    for picked in picked.reader.iter(&picked_events) {
+        eprintln!(
+            "picked {:?} at {:?}, normal {:?}",
+            picked.entity, picked.point, picked.normal
+        );
+    }
 }
 
 fn setup(
@@ -45,6 +68,7 @@ fn setup(
             material: floor_material,
             ..Default::default()
         })
+        .with(FocusIgnore)
         .spawn(LightComponents {
             translation: Translation::new(4.0, 5.0, -4.0),
             ..Default::default()
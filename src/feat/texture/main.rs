@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use bevy_gl::libs::{
     app::app_default,
     camera::{
+        camera_bookmarks::CameraBookmarksConfig,
         camera_info::CameraInfoConfig,
         camera_plugin::{AddCameraOpts, CameraTrait},
         camera_view::CameraViewOpts,
@@ -20,6 +21,11 @@ fn main() {
                 yaw: -135.00,
                 ..Default::default()
             },
+            bookmarks: Some(CameraBookmarksConfig {
+                feat_id: "feat_texture",
+                ..Default::default()
+            }),
+            ..Default::default()
         })
         .run();
 }
@@ -1,5 +1,9 @@
 use super::{
-    camera_config::CameraConfig, camera_position::CameraPosition, camera_view::CameraView,
+    camera_config::CameraConfig,
+    camera_lens::{CameraLens, ProjectionMode},
+    camera_motion::CameraMotion,
+    camera_position::CameraPosition,
+    camera_view::CameraView,
 };
 use bevy::{
     prelude::*,
@@ -14,6 +18,8 @@ pub struct Camera {
     pub view: CameraView,
     pub position: CameraPosition,
     pub config: CameraConfig,
+    pub motion: CameraMotion,
+    pub lens: CameraLens,
     pub camera: BevyCamera,
     pub perspective_projection: PerspectiveProjection,
     pub visible_entities: VisibleEntities,
@@ -23,10 +29,17 @@ pub struct Camera {
 impl Default for Camera {
     fn default() -> Self {
         let view = CameraView::new(&Default::default());
+        let config = CameraConfig::default();
         Self {
             view,
             position: Default::default(),
-            config: Default::default(),
+            config,
+            motion: CameraMotion::new(&view),
+            lens: CameraLens::new(
+                ProjectionMode::Perspective,
+                view.zoom,
+                config.focus_distance,
+            ),
             camera: BevyCamera {
                 name: Some(base::camera::CAMERA3D.to_string()),
                 ..Default::default()
@@ -0,0 +1,255 @@
+use super::camera_config::CameraSpeed;
+use crate::libs::util::load_from;
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::error::Error;
+
+/// Maps camera actions to the keys and mouse buttons that trigger them.
+/// The defaults match the original hard-coded W/A/S/D + left mouse drag controls.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraBindings {
+    pub forward: KeyCode,
+    pub backward: KeyCode,
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub up: KeyCode,
+    pub down: KeyCode,
+    pub sprint: KeyCode,
+    pub crawl: KeyCode,
+    pub roll_left: KeyCode,
+    pub roll_right: KeyCode,
+    /// Releases the cursor grabbed when `CameraConfig::grab_cursor` is enabled
+    pub release_cursor: KeyCode,
+    /// Switches between perspective and orthographic projection
+    pub toggle_projection: KeyCode,
+
+    /// Mouse button that needs to be held in order to look around (or orbit).
+    /// When `CameraConfig::grab_cursor` is enabled clicking it grabs the cursor instead.
+    pub look: MouseButton,
+    /// Mouse button that needs to be held in order to pan while in orbit mode
+    pub pan: MouseButton,
+}
+
+impl Default for CameraBindings {
+    fn default() -> Self {
+        CameraBindings {
+            forward: KeyCode::W,
+            backward: KeyCode::S,
+            left: KeyCode::A,
+            right: KeyCode::D,
+            up: KeyCode::Space,
+            down: KeyCode::C,
+            sprint: KeyCode::LShift,
+            crawl: KeyCode::LControl,
+            roll_left: KeyCode::Q,
+            roll_right: KeyCode::E,
+            release_cursor: KeyCode::Escape,
+            toggle_projection: KeyCode::O,
+            look: MouseButton::Left,
+            pan: MouseButton::Right,
+        }
+    }
+}
+
+impl CameraBindings {
+    /// Speed selected by the currently held modifier keys, sprint wins if both are held
+    pub fn speed(&self, keyboard_input: &Input<KeyCode>) -> CameraSpeed {
+        if keyboard_input.pressed(self.sprint) {
+            CameraSpeed::Sprint
+        } else if keyboard_input.pressed(self.crawl) {
+            CameraSpeed::Crawl
+        } else {
+            CameraSpeed::Normal
+        }
+    }
+
+    /// Parses bindings from RON, i.e. `(forward: Some("Z"), look: Some("Right"))`.
+    /// Actions that aren't provided keep their default binding.
+    pub fn from_ron(ron: &str) -> Result<Self, Box<dyn Error>> {
+        let file: CameraBindingsFile = ron::de::from_str(ron)?;
+        let mut bindings = CameraBindings::default();
+
+        set_key(&file.forward, &mut bindings.forward)?;
+        set_key(&file.backward, &mut bindings.backward)?;
+        set_key(&file.left, &mut bindings.left)?;
+        set_key(&file.right, &mut bindings.right)?;
+        set_key(&file.up, &mut bindings.up)?;
+        set_key(&file.down, &mut bindings.down)?;
+        set_key(&file.sprint, &mut bindings.sprint)?;
+        set_key(&file.crawl, &mut bindings.crawl)?;
+        set_key(&file.roll_left, &mut bindings.roll_left)?;
+        set_key(&file.roll_right, &mut bindings.roll_right)?;
+        set_key(&file.release_cursor, &mut bindings.release_cursor)?;
+        set_key(&file.toggle_projection, &mut bindings.toggle_projection)?;
+        set_mouse_button(&file.look, &mut bindings.look)?;
+        set_mouse_button(&file.pan, &mut bindings.pan)?;
+
+        Ok(bindings)
+    }
+
+    pub fn load(full_path: &str) -> Result<Self, Box<dyn Error>> {
+        let ron = load_from(full_path.to_string())?;
+        CameraBindings::from_ron(&ron)
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct CameraBindingsFile {
+    forward: Option<String>,
+    backward: Option<String>,
+    left: Option<String>,
+    right: Option<String>,
+    up: Option<String>,
+    down: Option<String>,
+    sprint: Option<String>,
+    crawl: Option<String>,
+    roll_left: Option<String>,
+    roll_right: Option<String>,
+    release_cursor: Option<String>,
+    toggle_projection: Option<String>,
+    look: Option<String>,
+    pan: Option<String>,
+}
+
+fn set_key(name: &Option<String>, key: &mut KeyCode) -> Result<(), Box<dyn Error>> {
+    if let Some(name) = name {
+        *key = key_code_from_str(name)
+            .ok_or_else(|| format!("Unknown key '{}' in camera bindings", name))?;
+    }
+    Ok(())
+}
+
+fn set_mouse_button(name: &Option<String>, button: &mut MouseButton) -> Result<(), Box<dyn Error>> {
+    if let Some(name) = name {
+        *button = mouse_button_from_str(name)
+            .ok_or_else(|| format!("Unknown mouse button '{}' in camera bindings", name))?;
+    }
+    Ok(())
+}
+
+fn mouse_button_from_str(name: &str) -> Option<MouseButton> {
+    let button = match name {
+        "Left" => MouseButton::Left,
+        "Right" => MouseButton::Right,
+        "Middle" => MouseButton::Middle,
+        other => MouseButton::Other(other.parse().ok()?),
+    };
+    Some(button)
+}
+
+// KeyCode isn't serializable, so we map the names of the keys that make sense for camera
+// controls by hand. Names match the KeyCode variants.
+pub(crate) fn key_code_from_str(name: &str) -> Option<KeyCode> {
+    let key = match name {
+        "A" => KeyCode::A,
+        "B" => KeyCode::B,
+        "C" => KeyCode::C,
+        "D" => KeyCode::D,
+        "E" => KeyCode::E,
+        "F" => KeyCode::F,
+        "G" => KeyCode::G,
+        "H" => KeyCode::H,
+        "I" => KeyCode::I,
+        "J" => KeyCode::J,
+        "K" => KeyCode::K,
+        "L" => KeyCode::L,
+        "M" => KeyCode::M,
+        "N" => KeyCode::N,
+        "O" => KeyCode::O,
+        "P" => KeyCode::P,
+        "Q" => KeyCode::Q,
+        "R" => KeyCode::R,
+        "S" => KeyCode::S,
+        "T" => KeyCode::T,
+        "U" => KeyCode::U,
+        "V" => KeyCode::V,
+        "W" => KeyCode::W,
+        "X" => KeyCode::X,
+        "Y" => KeyCode::Y,
+        "Z" => KeyCode::Z,
+        "Key0" => KeyCode::Key0,
+        "Key1" => KeyCode::Key1,
+        "Key2" => KeyCode::Key2,
+        "Key3" => KeyCode::Key3,
+        "Key4" => KeyCode::Key4,
+        "Key5" => KeyCode::Key5,
+        "Key6" => KeyCode::Key6,
+        "Key7" => KeyCode::Key7,
+        "Key8" => KeyCode::Key8,
+        "Key9" => KeyCode::Key9,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Space" => KeyCode::Space,
+        "Tab" => KeyCode::Tab,
+        "Return" => KeyCode::Return,
+        "Escape" => KeyCode::Escape,
+        "Back" => KeyCode::Back,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "LShift" => KeyCode::LShift,
+        "RShift" => KeyCode::RShift,
+        "LControl" => KeyCode::LControl,
+        "RControl" => KeyCode::RControl,
+        "LAlt" => KeyCode::LAlt,
+        "RAlt" => KeyCode::RAlt,
+        _ => return None,
+    };
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_shipped_bindings() {
+        let bindings = CameraBindings::load("resources/camera/azerty_bindings.ron").unwrap();
+        assert_eq!(
+            bindings,
+            CameraBindings {
+                forward: KeyCode::Z,
+                left: KeyCode::Q,
+                roll_left: KeyCode::A,
+                look: MouseButton::Right,
+                pan: MouseButton::Middle,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn missing_actions_keep_their_defaults() {
+        assert_eq!(
+            CameraBindings::from_ron("()").unwrap(),
+            CameraBindings::default()
+        );
+        let bindings =
+            CameraBindings::from_ron(r#"(sprint: Some("RShift"), look: Some("4"), up: None)"#)
+                .unwrap();
+        assert_eq!(bindings.sprint, KeyCode::RShift);
+        assert_eq!(bindings.look, MouseButton::Other(4));
+        assert_eq!(bindings.up, KeyCode::Space);
+        assert_eq!(bindings.forward, KeyCode::W);
+        assert_eq!(bindings.pan, MouseButton::Right);
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        let err = CameraBindings::from_ron(r#"(forward: Some("Keypad8"))"#).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown key 'Keypad8' in camera bindings");
+        let err = CameraBindings::from_ron(r#"(look: Some("Thumb"))"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown mouse button 'Thumb' in camera bindings"
+        );
+        // Names are case sensitive, like the KeyCode variants
+        assert_eq!(key_code_from_str("w"), None);
+        assert_eq!(key_code_from_str("LControl"), Some(KeyCode::LControl));
+        assert!(CameraBindings::from_ron(r#"(forward: Some(87))"#).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+use super::{
+    camera_motion::CameraMotion, camera_orbit::CameraOrbit, camera_position::CameraPosition,
+    camera_switch::ActiveCamera, camera_view::CameraView,
+};
+use crate::libs::util::{load_from_tmp, save_to_tmp};
+use bevy::{math::vec3, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error};
+
+const BOOKMARKS_FILE: &str = "camera_bookmarks.ron";
+
+const SLOT_KEYS: [(KeyCode, &str); 9] = [
+    (KeyCode::Key1, "1"),
+    (KeyCode::Key2, "2"),
+    (KeyCode::Key3, "3"),
+    (KeyCode::Key4, "4"),
+    (KeyCode::Key5, "5"),
+    (KeyCode::Key6, "6"),
+    (KeyCode::Key7, "7"),
+    (KeyCode::Key8, "8"),
+    (KeyCode::Key9, "9"),
+];
+
+/// A saved camera viewpoint
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CameraBookmark {
+    pub position: [f32; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Missing in files saved before roll was supported
+    #[serde(default)]
+    pub roll: f32,
+    pub zoom: f32,
+}
+
+impl CameraBookmark {
+    pub fn capture(view: &CameraView, position: &CameraPosition) -> Self {
+        let pos = position.pos();
+        CameraBookmark {
+            position: [pos.x(), pos.y(), pos.z()],
+            yaw: view.yaw,
+            pitch: view.pitch,
+            roll: view.roll,
+            zoom: view.zoom,
+        }
+    }
+
+    pub fn restore(&self, view: &mut CameraView, position: &mut CameraPosition) {
+        view.yaw = self.yaw;
+        view.pitch = self.pitch;
+        view.roll = self.roll;
+        view.zoom = self.zoom;
+        view.update_camera_vectors();
+        *position = vec3(self.position[0], self.position[1], self.position[2]).into();
+    }
+}
+
+/// Bookmarks by slot name, persisted as RON
+#[derive(Default, Debug, Serialize, Deserialize)]
+pub struct CameraBookmarks {
+    pub slots: BTreeMap<String, CameraBookmark>,
+}
+
+impl CameraBookmarks {
+    pub fn save(&self, feat_id: &str) -> Result<String, Box<dyn Error>> {
+        let ron = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        save_to_tmp(feat_id, BOOKMARKS_FILE, ron)
+    }
+
+    pub fn load(feat_id: &str) -> Result<(String, Self), Box<dyn Error>> {
+        let (full_path, ron) = load_from_tmp(feat_id, BOOKMARKS_FILE)?;
+        Ok((full_path, ron::de::from_str(&ron)?))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct CameraBookmarksConfig {
+    /// Identifies the feature the bookmarks are persisted for, see `libs::util::save_to_tmp`
+    pub feat_id: &'static str,
+    /// Hold this key and press a number key to store the current viewpoint in that slot,
+    /// press a number key by itself to jump back to it
+    pub capture: KeyCode,
+    /// Number keys only select slots while this key is held as well, so they can be bound
+    /// to something else by themselves
+    pub modifier: Option<KeyCode>,
+}
+
+impl Default for CameraBookmarksConfig {
+    fn default() -> Self {
+        CameraBookmarksConfig {
+            feat_id: "camera",
+            capture: KeyCode::B,
+            modifier: None,
+        }
+    }
+}
+
+fn load_bookmarks(config: Res<CameraBookmarksConfig>, mut bookmarks: ResMut<CameraBookmarks>) {
+    // Missing bookmarks just mean that none were saved yet
+    if let Ok((full_path, loaded)) = CameraBookmarks::load(config.feat_id) {
+        eprintln!(
+            "loaded {} camera bookmark(s) from {}",
+            loaded.slots.len(),
+            full_path
+        );
+        *bookmarks = loaded;
+    }
+}
+
+fn bookmark_keys_system(
+    config: Res<CameraBookmarksConfig>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    active_camera: Res<ActiveCamera>,
+    mut camera_query: Query<(
+        Entity,
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraMotion,
+        Option<&mut CameraOrbit>,
+    )>,
+) {
+    if let Some(modifier) = config.modifier {
+        if !keyboard_input.pressed(modifier) {
+            return;
+        }
+    }
+    let capturing = keyboard_input.pressed(config.capture);
+    for (key, slot) in SLOT_KEYS.iter() {
+        if !keyboard_input.just_pressed(*key) {
+            continue;
+        }
+        for (entity, mut view, mut position, mut motion, orbit) in &mut camera_query.iter() {
+            if !active_camera.is_active(entity) {
+                continue;
+            }
+            if capturing {
+                bookmarks
+                    .slots
+                    .insert(slot.to_string(), CameraBookmark::capture(&view, &position));
+                match bookmarks.save(config.feat_id) {
+                    Ok(full_path) => eprintln!("saved camera bookmark {} to {}", slot, full_path),
+                    Err(err) => eprintln!("failed to save camera bookmarks: {}", err),
+                }
+            } else if let Some(bookmark) = bookmarks.slots.get(*slot) {
+                bookmark.restore(&mut view, &mut position);
+                // Make sure smoothing and orbiting don't pull the camera back where it was
+                *motion = CameraMotion::new(&view);
+                if let Some(mut orbit) = orbit {
+                    orbit.align_to(&view, &position);
+                }
+            }
+        }
+    }
+}
+
+pub struct CameraBookmarksPlugin {
+    pub config: CameraBookmarksConfig,
+}
+
+impl Plugin for CameraBookmarksPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<CameraBookmarks>()
+            .add_resource(self.config)
+            .add_startup_system(load_bookmarks.system())
+            .add_system(bookmark_keys_system.system());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::camera::camera_view::CameraViewOpts;
+
+    struct Headless {
+        world: World,
+        resources: Resources,
+        schedule: Schedule,
+        camera: Entity,
+    }
+
+    impl Headless {
+        fn new(feat_id: &'static str) -> Self {
+            let mut world = World::new();
+            let view = CameraView::default();
+            let camera = world.spawn((
+                CameraMotion::new(&view),
+                view,
+                CameraPosition::from(vec3(1.0, 2.0, 3.0)),
+            ));
+            let mut resources = Resources::default();
+            resources.insert(CameraBookmarksConfig {
+                feat_id,
+                ..Default::default()
+            });
+            resources.insert(CameraBookmarks::default());
+            resources.insert(Input::<KeyCode>::default());
+            resources.insert(ActiveCamera::with_entity(camera));
+
+            let mut schedule = Schedule::default();
+            schedule.add_stage("update");
+            schedule.add_system_to_stage("update", bookmark_keys_system.system());
+            schedule.initialize(&mut resources);
+            Headless {
+                world,
+                resources,
+                schedule,
+                camera,
+            }
+        }
+
+        fn press(&mut self, keys: &[KeyCode]) {
+            let mut input = self.resources.get_mut::<Input<KeyCode>>().unwrap();
+            for key in keys {
+                input.press(*key);
+            }
+            drop(input);
+            self.schedule.run(&mut self.world, &mut self.resources);
+            let mut input = self.resources.get_mut::<Input<KeyCode>>().unwrap();
+            input.update();
+            for key in keys {
+                input.release(*key);
+            }
+        }
+
+        fn move_camera(&mut self, yaw: f32, pos: Vec3) {
+            let mut view = self.world.get_mut::<CameraView>(self.camera).unwrap();
+            view.yaw = yaw;
+            view.update_camera_vectors();
+            drop(view);
+            *self.world.get_mut::<CameraPosition>(self.camera).unwrap() = pos.into();
+        }
+
+        fn yaw(&self) -> f32 {
+            self.world.get::<CameraView>(self.camera).unwrap().yaw
+        }
+
+        fn pos(&self) -> Vec3 {
+            self.world.get::<CameraPosition>(self.camera).unwrap().pos()
+        }
+    }
+
+    #[test]
+    fn captured_viewpoint_is_restored() {
+        let mut headless = Headless::new("camera_bookmarks_test_keys");
+        headless.move_camera(-45.0, vec3(4.0, 5.0, 6.0));
+        headless.press(&[KeyCode::B, KeyCode::Key3]);
+        assert_eq!(
+            headless.resources.get::<CameraBookmarks>().unwrap().slots["3"],
+            CameraBookmark {
+                position: [4.0, 5.0, 6.0],
+                yaw: -45.0,
+                pitch: 0.0,
+                roll: 0.0,
+                zoom: 45.0,
+            }
+        );
+
+        headless.move_camera(90.0, vec3(-1.0, 0.0, 0.0));
+        // Empty slots leave the camera where it is
+        headless.press(&[KeyCode::Key4]);
+        assert_eq!(headless.yaw(), 90.0);
+        assert_eq!(headless.pos(), vec3(-1.0, 0.0, 0.0));
+
+        headless.press(&[KeyCode::Key3]);
+        assert_eq!(headless.yaw(), -45.0);
+        assert_eq!(headless.pos(), vec3(4.0, 5.0, 6.0));
+        // Smoothing heads for the restored view rather than the one before
+        let motion = headless.world.get::<CameraMotion>(headless.camera).unwrap();
+        assert_eq!(motion.target.yaw, -45.0);
+        assert_eq!(motion.velocity, Vec3::zero());
+    }
+
+    #[test]
+    fn bookmarks_survive_saving_and_loading() {
+        let feat_id = "camera_bookmarks_test_ron";
+        let mut view = CameraView::new(&CameraViewOpts {
+            yaw: 30.0,
+            pitch: -20.0,
+            roll: 10.0,
+        });
+        view.zoom = 60.0;
+        let mut bookmarks = CameraBookmarks::default();
+        bookmarks.slots.insert(
+            "1".to_string(),
+            CameraBookmark::capture(&view, &CameraPosition::from(vec3(1.5, -2.0, 8.0))),
+        );
+        bookmarks.slots.insert(
+            "9".to_string(),
+            CameraBookmark::capture(&CameraView::default(), &CameraPosition::default()),
+        );
+        bookmarks.save(feat_id).unwrap();
+
+        let (_, loaded) = CameraBookmarks::load(feat_id).unwrap();
+        assert_eq!(loaded.slots, bookmarks.slots);
+
+        let mut restored_view = CameraView::default();
+        let mut restored_position = CameraPosition::default();
+        loaded.slots["1"].restore(&mut restored_view, &mut restored_position);
+        assert_eq!(restored_view.yaw, 30.0);
+        assert_eq!(restored_view.pitch, -20.0);
+        assert_eq!(restored_view.roll, 10.0);
+        assert_eq!(restored_view.zoom, 60.0);
+        assert!((restored_view.front - view.front).length() < 0.0001);
+        assert_eq!(restored_position.pos(), vec3(1.5, -2.0, 8.0));
+    }
+
+    #[test]
+    fn bookmarks_without_roll_still_load() {
+        let ron = r#"(
+            slots: {
+                "2": (position: (0.0, 1.0, 2.0), yaw: -90.0, pitch: 5.0, zoom: 45.0),
+            },
+        )"#;
+        let bookmarks: CameraBookmarks = ron::de::from_str(ron).unwrap();
+        assert_eq!(bookmarks.slots["2"].roll, 0.0);
+        assert_eq!(bookmarks.slots["2"].position, [0.0, 1.0, 2.0]);
+    }
+}
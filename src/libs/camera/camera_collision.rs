@@ -0,0 +1,407 @@
+use super::{
+    camera_config::CameraConfig, camera_orbit::CameraOrbit, camera_position::CameraPosition,
+};
+use bevy::{math::vec3, prelude::*};
+
+/// Number of passes used to resolve collisions, needed when pushing the camera out of one
+/// collider pushes it into another one
+const COLLISION_PASSES: usize = 3;
+
+/// Axis aligned box the camera is kept inside of
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraBounds {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl CameraBounds {
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+}
+
+/// Plane at height `y` the camera stays at least `min_height` above
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraGround {
+    pub y: f32,
+    pub min_height: f32,
+}
+
+/// Axis aligned box around the entity's `Translation` the camera can't move through.
+/// Note that rotation and scale of the entity are not taken into account.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraCollider {
+    pub half_extents: Vec3,
+    pub offset: Vec3,
+}
+
+impl CameraCollider {
+    pub fn new(half_extents: Vec3) -> Self {
+        CameraCollider {
+            half_extents,
+            offset: Vec3::zero(),
+        }
+    }
+
+    /// The world space `(min, max)` corners of the collider for an entity at `translation`
+    pub fn aabb(&self, translation: Vec3) -> (Vec3, Vec3) {
+        let center = translation + self.offset;
+        (center - self.half_extents, center + self.half_extents)
+    }
+}
+
+pub fn closest_point_on_aabb(point: Vec3, min: Vec3, max: Vec3) -> Vec3 {
+    point.max(min).min(max)
+}
+
+/// Pushes a sphere at `center` out of the box, along the shortest way out.
+/// Returns `None` if the sphere doesn't intersect the box.
+/// Since only the penetrating part of the movement is undone the sphere slides along the
+/// surface of the box when moving into it at an angle.
+pub fn resolve_sphere_aabb(center: Vec3, radius: f32, min: Vec3, max: Vec3) -> Option<Vec3> {
+    let everywhere = CameraBounds {
+        min: Vec3::splat(f32::NEG_INFINITY),
+        max: Vec3::splat(f32::INFINITY),
+    };
+    resolve_sphere_aabb_within(center, radius, min, max, &everywhere)
+}
+
+/// Like `resolve_sphere_aabb`, but only pushes the center to where it stays inside `region`.
+/// When the shortest way out of the box leaves the region, e.g. below the ground, the sphere
+/// leaves through the closest face that keeps it inside instead.
+pub fn resolve_sphere_aabb_within(
+    center: Vec3,
+    radius: f32,
+    min: Vec3,
+    max: Vec3,
+    region: &CameraBounds,
+) -> Option<Vec3> {
+    let closest = closest_point_on_aabb(center, min, max);
+    let delta = center - closest;
+    let distance_squared = delta.length_squared();
+
+    if distance_squared > 0.0 {
+        if distance_squared >= radius * radius {
+            return None;
+        }
+        let distance = distance_squared.sqrt();
+        let pushed = closest + delta / distance * radius;
+        if region.contains(pushed) {
+            return Some(pushed);
+        }
+    }
+
+    // Center is inside the box (or the push left the region), leave through the closest face
+    let mut exits = [
+        vec3(min.x() - radius, center.y(), center.z()),
+        vec3(max.x() + radius, center.y(), center.z()),
+        vec3(center.x(), min.y() - radius, center.z()),
+        vec3(center.x(), max.y() + radius, center.z()),
+        vec3(center.x(), center.y(), min.z() - radius),
+        vec3(center.x(), center.y(), max.z() + radius),
+    ];
+    // Stable, so the first of equally close faces wins
+    exits.sort_by(|a, b| {
+        (*a - center)
+            .length_squared()
+            .partial_cmp(&(*b - center).length_squared())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let exit = exits
+        .iter()
+        .copied()
+        .find(|exit| region.contains(*exit))
+        // The box fills the region, get as close to leaving it as the region allows
+        .unwrap_or_else(|| clamp_to_bounds(exits[0], 0.0, region));
+    Some(exit)
+}
+
+/// Keeps a sphere at `center` inside the bounds, as far as they are large enough to fit it
+pub fn clamp_to_bounds(center: Vec3, radius: f32, bounds: &CameraBounds) -> Vec3 {
+    let inset = Vec3::splat(radius);
+    let min = bounds.min + inset;
+    let max = (bounds.max - inset).max(min);
+    center.max(min).min(max)
+}
+
+pub fn clamp_to_ground(center: Vec3, ground: &CameraGround) -> Vec3 {
+    let min_y = ground.y + ground.min_height;
+    if center.y() < min_y {
+        vec3(center.x(), min_y, center.z())
+    } else {
+        center
+    }
+}
+
+/// Region the center of a camera with the given collision `radius` has to stay in to respect
+/// its bounds and ground
+pub fn free_region(config: &CameraConfig, radius: f32) -> CameraBounds {
+    let mut min = Vec3::splat(f32::NEG_INFINITY);
+    let mut max = Vec3::splat(f32::INFINITY);
+    if let Some(ground) = &config.ground {
+        min = vec3(min.x(), ground.y + ground.min_height, min.z());
+    }
+    if let Some(bounds) = &config.bounds {
+        let inset = Vec3::splat(radius);
+        min = min.max(bounds.min + inset);
+        max = bounds.max - inset;
+    }
+    CameraBounds {
+        min,
+        max: max.max(min),
+    }
+}
+
+/// Applies all constraints configured for the camera to `position`.
+/// `colliders` are the `(min, max)` corners of the boxes the camera collides with.
+/// Bounds and ground are applied first and colliders only push the camera to where it stays
+/// within them, so clamping can't move the camera back into a collider.
+pub fn constrain_position(
+    position: Vec3,
+    config: &CameraConfig,
+    colliders: &[(Vec3, Vec3)],
+) -> Vec3 {
+    let region = free_region(config, config.collision_radius.unwrap_or(0.0));
+    let mut position = clamp_to_bounds(position, 0.0, &region);
+    if let Some(radius) = config.collision_radius {
+        for _ in 0..COLLISION_PASSES {
+            let mut resolved = false;
+            for (min, max) in colliders {
+                if let Some(pushed) =
+                    resolve_sphere_aabb_within(position, radius, *min, *max, &region)
+                {
+                    position = pushed;
+                    resolved = true;
+                }
+            }
+            if !resolved {
+                break;
+            }
+        }
+    }
+    position
+}
+
+pub(crate) fn camera_collision_system(
+    mut collider_query: Query<(&CameraCollider, &Translation)>,
+    mut camera_query: Query<(&mut CameraPosition, &CameraConfig, Option<&CameraOrbit>)>,
+) {
+    let mut colliders: Option<Vec<(Vec3, Vec3)>> = None;
+    for (mut position, config, orbit) in &mut camera_query.iter() {
+        // Orbit cameras are positioned by their orbit, constraining them would fight it
+        if orbit.is_some() || !config.is_constrained() {
+            continue;
+        }
+        // Only collect colliders once there is a camera that collides with them
+        let colliders = colliders.get_or_insert_with(|| {
+            let mut aabbs = Vec::new();
+            for (collider, translation) in &mut collider_query.iter() {
+                aabbs.push(collider.aabb(translation.0));
+            }
+            aabbs
+        });
+        let constrained = constrain_position(position.pos(), config, colliders);
+        if constrained != position.pos() {
+            *position = constrained.into();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIUS: f32 = 0.5;
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual - expected).length() < 0.0001,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    fn unit_box() -> (Vec3, Vec3) {
+        (Vec3::splat(-1.0), Vec3::splat(1.0))
+    }
+
+    #[test]
+    fn bounds_keep_the_sphere_inside() {
+        let bounds = CameraBounds {
+            min: Vec3::splat(-10.0),
+            max: Vec3::splat(10.0),
+        };
+        let inside = vec3(1.0, 2.0, 3.0);
+        assert_eq!(clamp_to_bounds(inside, RADIUS, &bounds), inside);
+        assert_near(
+            clamp_to_bounds(vec3(20.0, -15.0, 3.0), RADIUS, &bounds),
+            vec3(9.5, -9.5, 3.0),
+        );
+
+        // Bounds too small to fit the sphere pin it to their min corner
+        let tiny = CameraBounds {
+            min: Vec3::zero(),
+            max: Vec3::splat(0.5),
+        };
+        assert_near(
+            clamp_to_bounds(vec3(5.0, 5.0, 5.0), RADIUS, &tiny),
+            Vec3::splat(RADIUS),
+        );
+    }
+
+    #[test]
+    fn ground_keeps_the_camera_above_it() {
+        let ground = CameraGround {
+            y: 1.0,
+            min_height: 0.5,
+        };
+        assert_eq!(
+            clamp_to_ground(vec3(2.0, -3.0, 4.0), &ground),
+            vec3(2.0, 1.5, 4.0)
+        );
+        assert_eq!(
+            clamp_to_ground(vec3(2.0, 3.0, 4.0), &ground),
+            vec3(2.0, 3.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn sphere_outside_the_box_is_not_pushed() {
+        let (min, max) = unit_box();
+        assert_eq!(
+            resolve_sphere_aabb(vec3(2.0, 0.0, 0.0), RADIUS, min, max),
+            None
+        );
+        // Clear of the edge even though it's within the radius along both axes
+        assert_eq!(
+            resolve_sphere_aabb(vec3(1.4, 1.4, 0.0), RADIUS, min, max),
+            None
+        );
+    }
+
+    #[test]
+    fn sphere_slides_along_a_face() {
+        let (min, max) = unit_box();
+        // Only the part of the movement into the face is undone
+        let pushed = resolve_sphere_aabb(vec3(1.2, 0.3, -0.4), RADIUS, min, max).unwrap();
+        assert_near(pushed, vec3(1.5, 0.3, -0.4));
+        let pushed = resolve_sphere_aabb(vec3(0.2, -0.6, -1.1), RADIUS, min, max).unwrap();
+        assert_near(pushed, vec3(0.2, -0.6, -1.5));
+    }
+
+    #[test]
+    fn sphere_is_pushed_away_from_edges_and_corners() {
+        let (min, max) = unit_box();
+        let edge = resolve_sphere_aabb(vec3(1.2, 1.2, 0.0), RADIUS, min, max).unwrap();
+        assert_near(
+            edge,
+            vec3(1.0, 1.0, 0.0) + vec3(1.0, 1.0, 0.0).normalize() * RADIUS,
+        );
+
+        let corner = resolve_sphere_aabb(vec3(1.1, -1.1, 1.1), RADIUS, min, max).unwrap();
+        assert_near(
+            corner,
+            vec3(1.0, -1.0, 1.0) + vec3(1.0, -1.0, 1.0).normalize() * RADIUS,
+        );
+    }
+
+    #[test]
+    fn center_inside_the_box_leaves_through_the_closest_face() {
+        let (min, max) = unit_box();
+        let pushed = resolve_sphere_aabb(vec3(0.8, 0.1, -0.2), RADIUS, min, max).unwrap();
+        assert_near(pushed, vec3(1.5, 0.1, -0.2));
+        let pushed = resolve_sphere_aabb(vec3(0.1, 0.0, -0.9), RADIUS, min, max).unwrap();
+        assert_near(pushed, vec3(0.1, 0.0, -1.5));
+    }
+
+    #[test]
+    fn region_limits_the_way_out_of_the_box() {
+        let (min, max) = unit_box();
+        let above_ground = CameraBounds {
+            min: vec3(f32::NEG_INFINITY, -0.95, f32::NEG_INFINITY),
+            max: Vec3::splat(f32::INFINITY),
+        };
+        // Leaving through the bottom face would be shorter
+        let pushed =
+            resolve_sphere_aabb_within(vec3(0.3, -0.9, 0.0), RADIUS, min, max, &above_ground);
+        assert_near(pushed.unwrap(), vec3(1.5, -0.9, 0.0));
+        // Pushes that stay inside the region are the same as without one
+        assert_eq!(
+            resolve_sphere_aabb_within(vec3(1.2, 0.3, -0.4), RADIUS, min, max, &above_ground),
+            resolve_sphere_aabb(vec3(1.2, 0.3, -0.4), RADIUS, min, max),
+        );
+        assert!(above_ground.contains(vec3(0.0, -0.95, 0.0)));
+        assert!(!above_ground.contains(vec3(0.0, -1.0, 0.0)));
+    }
+
+    #[test]
+    fn collider_on_the_ground_doesnt_trap_the_camera() {
+        let config = CameraConfig {
+            collision_radius: Some(RADIUS),
+            ground: Some(CameraGround {
+                y: 0.0,
+                min_height: 0.5,
+            }),
+            ..Default::default()
+        };
+        // A box resting on the ground
+        let (min, max) = (vec3(-1.0, 0.0, -1.0), vec3(1.0, 1.0, 1.0));
+        let colliders = [(min, max)];
+        let free = |position: Vec3| {
+            position.y() >= 0.5 && resolve_sphere_aabb(position, RADIUS, min, max).is_none()
+        };
+
+        // Inside near the bottom, leaving downwards would end up below the ground
+        let constrained = constrain_position(vec3(0.2, 0.1, 0.0), &config, &colliders);
+        assert_near(constrained, vec3(0.2, 1.5, 0.0));
+        assert!(free(constrained));
+
+        // Beside the box below the minimum height, raised and then slid along the side
+        let constrained = constrain_position(vec3(1.2, 0.0, 0.3), &config, &colliders);
+        assert_near(constrained, vec3(1.5, 0.5, 0.3));
+        assert!(free(constrained));
+
+        // Below a box hovering less than a sphere above the minimum height
+        let hovering = [(vec3(-1.0, 0.8, -1.0), vec3(1.0, 3.0, 1.0))];
+        let constrained = constrain_position(vec3(0.0, 0.2, 0.0), &config, &hovering);
+        assert_near(constrained, vec3(-1.5, 0.5, 0.0));
+        assert!(resolve_sphere_aabb(constrained, RADIUS, hovering[0].0, hovering[0].1).is_none());
+    }
+
+    #[test]
+    fn constrain_position_applies_all_constraints() {
+        let config = CameraConfig {
+            collision_radius: Some(RADIUS),
+            ground: Some(CameraGround {
+                y: 0.0,
+                min_height: 1.0,
+            }),
+            bounds: Some(CameraBounds {
+                min: Vec3::splat(-5.0),
+                max: Vec3::splat(5.0),
+            }),
+            ..Default::default()
+        };
+        let colliders = [unit_box()];
+        assert_near(
+            constrain_position(vec3(0.0, 0.9, 0.0), &config, &colliders),
+            vec3(0.0, 1.5, 0.0),
+        );
+        assert_near(
+            constrain_position(vec3(3.0, -2.0, 0.0), &config, &colliders),
+            vec3(3.0, 1.0, 0.0),
+        );
+        assert_near(
+            constrain_position(vec3(9.0, 2.0, 0.0), &config, &colliders),
+            vec3(4.5, 2.0, 0.0),
+        );
+
+        // Unconstrained cameras go anywhere
+        let free = vec3(0.0, -100.0, 0.0);
+        assert_eq!(
+            constrain_position(free, &CameraConfig::default(), &colliders),
+            free
+        );
+    }
+}
@@ -1,7 +1,98 @@
+use super::{
+    camera_collision::{CameraBounds, CameraGround},
+    camera_motion::CameraSmoothing,
+};
+use bevy::prelude::*;
+
+#[cfg(feature = "gamepad")]
+use super::camera_gamepad::CameraGamepadConfig;
+
+/// Registered with the type registry so that it is persisted along with scenes.
+/// Only the plain settings are persisted, the ignored ones aren't supported by the property
+/// system and are taken from the `AddCameraOpts` the camera was added with.
+#[derive(Clone, Copy, Debug, Properties)]
 pub struct CameraConfig {
     pub mov_speed: f32,
     pub mouse_sensitivity: f32,
     pub constrain_pitch: bool,
+    /// Moving the mouse up looks down, enable this if vertical mouse/trackpad drags feel
+    /// backwards
+    pub invert_y: bool,
+    /// Clicking the look mouse button grabs and hides the cursor so that mouse motion
+    /// looks around without holding the button, the release cursor key gives it back
+    pub grab_cursor: bool,
+    /// Rotates the camera around its own axes, allowing it to roll and pitch without limits
+    pub six_dof: bool,
+    /// Degrees per millisecond the camera rolls while a roll key is held in 6-DoF mode
+    pub roll_speed: f32,
+
+    /// Smallest zoom, i.e. field of view in degrees, the mouse wheel can zoom in to
+    pub min_zoom: f32,
+    /// Largest zoom, i.e. field of view in degrees, the mouse wheel can zoom out to
+    pub max_zoom: f32,
+
+    /// Distance at which objects keep their size when switching between perspective and
+    /// orthographic projection, orbit cameras use their distance to the target instead
+    pub focus_distance: f32,
+
+    /// Multiplies `mov_speed` while the sprint key is held
+    pub sprint_multiplier: f32,
+    /// Multiplies `mov_speed` while the crawl key is held
+    pub crawl_multiplier: f32,
+    /// Axis along which `CameraMovement::Up` and `CameraMovement::Down` move the camera
+    #[property(ignore)]
+    pub vertical_axis: VerticalAxis,
+    /// Enables acceleration/damping for movement and easing for look and zoom
+    #[property(ignore)]
+    pub smoothing: Option<CameraSmoothing>,
+
+    /// Box the camera can't leave
+    #[property(ignore)]
+    pub bounds: Option<CameraBounds>,
+    /// Ground plane the camera can't go below
+    #[property(ignore)]
+    pub ground: Option<CameraGround>,
+    /// Radius of the sphere that collides with entities that have a `CameraCollider`
+    #[property(ignore)]
+    pub collision_radius: Option<f32>,
+
+    /// Skips drawing meshes whose bounds are outside of the camera's view.
+    /// Off by default since bevy draws everything otherwise.
+    pub frustum_culling: bool,
+
+    /// Deadzones and sensitivity of gamepad input
+    #[cfg(feature = "gamepad")]
+    #[property(ignore)]
+    pub gamepad: CameraGamepadConfig,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VerticalAxis {
+    /// Move straight up/down regardless of where the camera is looking
+    World,
+    /// Move along the camera's own up vector
+    Camera,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraSpeed {
+    Normal,
+    Sprint,
+    Crawl,
+}
+
+impl CameraConfig {
+    pub fn is_constrained(&self) -> bool {
+        self.bounds.is_some() || self.ground.is_some() || self.collision_radius.is_some()
+    }
+
+    pub fn speed(&self, speed: CameraSpeed) -> f32 {
+        match speed {
+            CameraSpeed::Normal => self.mov_speed,
+            CameraSpeed::Sprint => self.mov_speed * self.sprint_multiplier,
+            CameraSpeed::Crawl => self.mov_speed * self.crawl_multiplier,
+        }
+    }
 }
 
 impl Default for CameraConfig {
@@ -10,6 +101,23 @@ impl Default for CameraConfig {
             mov_speed: 0.01,
             mouse_sensitivity: 0.1,
             constrain_pitch: true,
+            invert_y: false,
+            grab_cursor: false,
+            six_dof: false,
+            roll_speed: 0.09,
+            min_zoom: 1.0,
+            max_zoom: 45.0,
+            focus_distance: 10.0,
+            sprint_multiplier: 3.0,
+            crawl_multiplier: 0.25,
+            vertical_axis: VerticalAxis::World,
+            smoothing: None,
+            bounds: None,
+            ground: None,
+            collision_radius: None,
+            frustum_culling: false,
+            #[cfg(feature = "gamepad")]
+            gamepad: Default::default(),
         }
     }
 }
@@ -0,0 +1,297 @@
+use super::{
+    camera_config::CameraConfig, camera_lens::CameraLens, camera_picking::mesh_aabb,
+    camera_position::CameraPosition, camera_switch::ActiveCamera, camera_view::CameraView,
+};
+use bevy::{
+    prelude::*,
+    render::camera::{PerspectiveProjection, VisibleEntities},
+};
+use std::collections::HashMap;
+
+/// The six planes bounding what a camera sees. Each plane is stored as `(normal, distance)`
+/// packed into a `Vec4`, with the normal pointing into the frustum.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    pub planes: [Vec4; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes from a combined `projection * view` matrix (Gribb/Hartmann).
+    /// Assumes a depth range of 0 to 1, which is what bevy's projections use.
+    pub fn from_matrix(view_projection: &Mat4) -> Self {
+        // Rows of the matrix are the columns of its transpose
+        let m = view_projection.transpose();
+        let (row0, row1, row2, row3) = (m.x_axis(), m.y_axis(), m.z_axis(), m.w_axis());
+        let planes = [
+            row3 + row0, // left
+            row3 - row0, // right
+            row3 + row1, // bottom
+            row3 - row1, // top
+            row2,        // near
+            row3 - row2, // far
+        ];
+        let mut normalized = [Vec4::zero(); 6];
+        for (plane, normalized) in planes.iter().zip(normalized.iter_mut()) {
+            let length = plane.truncate().length();
+            *normalized = if length > 0.0 {
+                *plane / length
+            } else {
+                *plane
+            };
+        }
+        Frustum { planes: normalized }
+    }
+
+    /// Frustum of a camera placed via `CameraView::get_view`, which is the camera's
+    /// transform rather than the view matrix, hence the inverse
+    pub fn from_camera(
+        view: &CameraView,
+        position: &CameraPosition,
+        projection: &PerspectiveProjection,
+        lens: &CameraLens,
+    ) -> Self {
+        let view_matrix = view.get_view(position).inverse();
+        Frustum::from_matrix(&(lens.projection_matrix(projection) * view_matrix))
+    }
+
+    /// Returns `false` only if the box from `min` to `max` is entirely outside of one of
+    /// the planes. Boxes near the corners of the frustum may be reported as inside even
+    /// though they aren't, which is fine for culling.
+    pub fn intersects_aabb(&self, min: Vec3, max: Vec3) -> bool {
+        self.planes.iter().all(|plane| {
+            let normal = plane.truncate();
+            // Corner of the box furthest along the plane normal
+            let positive = Vec3::new(
+                if normal.x() >= 0.0 { max.x() } else { min.x() },
+                if normal.y() >= 0.0 { max.y() } else { min.y() },
+                if normal.z() >= 0.0 { max.z() } else { min.z() },
+            );
+            normal.dot(positive) + plane.w() >= 0.0
+        })
+    }
+
+    pub fn intersects_sphere(&self, center: Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(center) + plane.w() >= -radius)
+    }
+}
+
+/// World space `(min, max)` corners of the box containing the local space box from `min` to
+/// `max` after transforming it by `transform`
+pub fn transform_aabb(min: Vec3, max: Vec3, transform: &Mat4) -> (Vec3, Vec3) {
+    let center = transform.transform_point3((min + max) * 0.5);
+    let extents = (max - min) * 0.5;
+    // Each world axis extent is the sum of the local extents projected onto it
+    let x = transform.x_axis().truncate() * extents.x();
+    let y = transform.y_axis().truncate() * extents.y();
+    let z = transform.z_axis().truncate() * extents.z();
+    let world_extents = x.abs() + y.abs() + z.abs();
+    (center - world_extents, center + world_extents)
+}
+
+/// Local space bounds of meshes, computed once per mesh and recomputed when it changes
+#[derive(Default)]
+pub struct MeshBounds {
+    mesh_events: EventReader<AssetEvent<Mesh>>,
+    bounds: HashMap<Handle<Mesh>, Option<(Vec3, Vec3)>>,
+}
+
+impl MeshBounds {
+    /// Forgets the bounds of meshes that were modified or removed
+    pub fn update(&mut self, mesh_events: &Events<AssetEvent<Mesh>>) {
+        for event in self.mesh_events.iter(mesh_events) {
+            match event {
+                AssetEvent::Modified { handle } | AssetEvent::Removed { handle } => {
+                    self.bounds.remove(handle);
+                }
+                AssetEvent::Created { .. } => {}
+            }
+        }
+    }
+
+    /// Local space `(min, max)` corners of the mesh, `None` if it isn't loaded yet or
+    /// has no positions
+    pub fn get(&mut self, handle: Handle<Mesh>, meshes: &Assets<Mesh>) -> Option<(Vec3, Vec3)> {
+        if let Some(bounds) = self.bounds.get(&handle) {
+            return *bounds;
+        }
+        let mesh = meshes.get(&handle)?;
+        let bounds = mesh_aabb(mesh);
+        self.bounds.insert(handle, bounds);
+        bounds
+    }
+
+    /// World space `(min, max)` corners of the mesh with the given world `transform`
+    pub fn world_aabb(
+        &mut self,
+        handle: Handle<Mesh>,
+        meshes: &Assets<Mesh>,
+        transform: &Transform,
+    ) -> Option<(Vec3, Vec3)> {
+        let (min, max) = self.get(handle, meshes)?;
+        Some(transform_aabb(min, max, &transform.value))
+    }
+}
+
+/// Number of entities the active camera draws and skipped during the last frame
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CameraCullingStats {
+    pub visible: usize,
+    pub culled: usize,
+}
+
+/// Runs right after POST_UPDATE, where bevy's visible_entities_system fills VisibleEntities
+/// with everything that is drawable, and before anything is drawn
+pub(crate) const CULLING_STAGE: &str = "camera_culling";
+
+// Removes what the camera can't see from the VisibleEntities of its camera, so it needs to
+// run in CULLING_STAGE.
+pub(crate) fn frustum_culling_system(
+    meshes: Res<Assets<Mesh>>,
+    mesh_events: Res<Events<AssetEvent<Mesh>>>,
+    active_camera: Res<ActiveCamera>,
+    mut mesh_bounds: ResMut<MeshBounds>,
+    mut stats: ResMut<CameraCullingStats>,
+    mut camera_query: Query<(
+        Entity,
+        &CameraView,
+        &CameraPosition,
+        &PerspectiveProjection,
+        &CameraLens,
+        &CameraConfig,
+        &mut VisibleEntities,
+    )>,
+    mesh_query: Query<(&Handle<Mesh>, &Transform)>,
+) {
+    mesh_bounds.update(&mesh_events);
+
+    for (entity, view, position, projection, lens, config, mut visible_entities) in
+        &mut camera_query.iter()
+    {
+        let total = visible_entities.value.len();
+        if config.frustum_culling {
+            let frustum = Frustum::from_camera(view, position, projection, lens);
+            visible_entities.value.retain(|visible| {
+                let handle = match mesh_query.get::<Handle<Mesh>>(visible.entity) {
+                    Ok(handle) => *handle,
+                    Err(_) => return true,
+                };
+                let transform = match mesh_query.get::<Transform>(visible.entity) {
+                    Ok(transform) => transform,
+                    Err(_) => return true,
+                };
+                // Keep meshes whose bounds aren't known yet
+                match mesh_bounds.world_aabb(handle, &meshes, &transform) {
+                    Some((min, max)) => frustum.intersects_aabb(min, max),
+                    None => true,
+                }
+            });
+        }
+        if active_camera.is_active(entity) {
+            let visible = visible_entities.value.len();
+            *stats = CameraCullingStats {
+                visible,
+                culled: total - visible,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::vec3;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual - expected).length() < 0.0001,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    /// Camera at `eye` looking down -z with a 90 degree fov, near plane at 1 and far plane
+    /// at 100
+    fn frustum_at(eye: Vec3) -> Frustum {
+        let projection = Mat4::perspective_rh(FRAC_PI_2, 1.0, 1.0, 100.0);
+        let view = Mat4::from_translation(-eye);
+        Frustum::from_matrix(&(projection * view))
+    }
+
+    #[test]
+    fn planes_are_normalized_and_point_inside() {
+        let frustum = frustum_at(Vec3::zero());
+        for plane in frustum.planes.iter() {
+            assert!((plane.truncate().length() - 1.0).abs() < 0.0001);
+            // A point on the view axis between near and far is inside of every plane
+            assert!(plane.truncate().dot(vec3(0.0, 0.0, -10.0)) + plane.w() > 0.0);
+        }
+        let near = frustum.planes[4];
+        assert_near(near.truncate(), -Vec3::unit_z());
+        assert!((near.w() + 1.0).abs() < 0.0001, "{:?}", near);
+    }
+
+    #[test]
+    fn boxes_in_front_behind_and_straddling_the_near_plane() {
+        let frustum = frustum_at(Vec3::zero());
+        let unit = Vec3::splat(0.5);
+        let at = |center: Vec3| frustum.intersects_aabb(center - unit, center + unit);
+        assert!(at(vec3(0.0, 0.0, -5.0)), "in front");
+        assert!(!at(vec3(0.0, 0.0, 5.0)), "behind");
+        assert!(at(vec3(0.0, 0.0, -1.0)), "straddling the near plane");
+        assert!(
+            !at(vec3(0.0, 0.0, -0.2)),
+            "between the camera and the near plane"
+        );
+        assert!(at(vec3(0.0, 0.0, -100.0)), "straddling the far plane");
+        assert!(!at(vec3(0.0, 0.0, -102.0)), "beyond the far plane");
+        assert!(!at(vec3(-10.0, 0.0, -5.0)), "left of the view");
+        assert!(!at(vec3(0.0, 10.0, -5.0)), "above the view");
+        // Larger than the view in every direction
+        assert!(frustum.intersects_aabb(Vec3::splat(-500.0), Vec3::splat(500.0)));
+    }
+
+    #[test]
+    fn frustum_moves_with_the_camera() {
+        let frustum = frustum_at(vec3(0.0, 0.0, 10.0));
+        let unit = Vec3::splat(0.5);
+        assert!(frustum.intersects_aabb(-unit, unit));
+        assert!(!frustum.intersects_aabb(vec3(0.0, 0.0, 12.0) - unit, vec3(0.0, 0.0, 12.0) + unit));
+    }
+
+    #[test]
+    fn transform_aabb_translates_rotates_and_scales() {
+        let (min, max) = (vec3(0.0, -1.0, -1.0), vec3(2.0, 1.0, 1.0));
+        let (world_min, world_max) = transform_aabb(min, max, &Mat4::identity());
+        assert_near(world_min, min);
+        assert_near(world_max, max);
+
+        let translation = Mat4::from_translation(vec3(1.0, 2.0, 3.0));
+        let (world_min, world_max) = transform_aabb(min, max, &translation);
+        assert_near(world_min, vec3(1.0, 1.0, 2.0));
+        assert_near(world_max, vec3(3.0, 3.0, 4.0));
+
+        // +x turns into -z
+        let rotation = Mat4::from_rotation_y(FRAC_PI_2);
+        let (world_min, world_max) = transform_aabb(min, max, &rotation);
+        assert_near(world_min, vec3(-1.0, -1.0, -2.0));
+        assert_near(world_max, vec3(1.0, 1.0, 0.0));
+
+        let scale = Mat4::from_scale(Vec3::splat(2.0));
+        let (world_min, world_max) = transform_aabb(min, max, &scale);
+        assert_near(world_min, min * 2.0);
+        assert_near(world_max, max * 2.0);
+    }
+
+    #[test]
+    fn rotated_box_grows_to_contain_its_corners() {
+        let unit = Vec3::splat(0.5);
+        let (min, max) = transform_aabb(-unit, unit, &Mat4::from_rotation_y(FRAC_PI_2 / 2.0));
+        let half_diagonal = 0.5 * 2.0f32.sqrt();
+        assert_near(min, vec3(-half_diagonal, -0.5, -half_diagonal));
+        assert_near(max, vec3(half_diagonal, 0.5, half_diagonal));
+    }
+}
@@ -0,0 +1,349 @@
+use super::{
+    camera_culling::MeshBounds, camera_lens::CameraLens, camera_motion::CameraMotion,
+    camera_orbit::CameraOrbit, camera_position::CameraPosition, camera_switch::ActiveCamera,
+    camera_view::CameraView,
+};
+use bevy::{prelude::*, render::camera::PerspectiveProjection};
+
+/// Request to move the active camera so that `target` fills the view, or all meshes without
+/// `FocusIgnore` if `target` is `None`
+#[derive(Clone, Copy, Debug)]
+pub struct FocusCamera {
+    pub target: Option<Entity>,
+}
+
+/// Keeps a mesh out of the frame when focusing all meshes, i.e. the floor or editor handles.
+/// Focusing the entity itself still frames it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FocusIgnore;
+
+#[derive(Clone, Copy)]
+pub struct CameraFocusConfig {
+    /// Frames all meshes in the scene, except those with `FocusIgnore`
+    pub key: KeyCode,
+    /// Seconds the camera takes to fly to the framed object, 0 moves it there right away
+    pub duration: f32,
+    /// Scales the distance to the framed object, values above 1 leave some room around it
+    pub margin: f32,
+}
+
+impl Default for CameraFocusConfig {
+    fn default() -> Self {
+        CameraFocusConfig {
+            key: KeyCode::F,
+            duration: 0.5,
+            margin: 1.1,
+        }
+    }
+}
+
+/// Union of two `(min, max)` boxes
+pub fn union_aabb(a: (Vec3, Vec3), b: (Vec3, Vec3)) -> (Vec3, Vec3) {
+    (a.0.min(b.0), a.1.max(b.1))
+}
+
+/// Distance at which a sphere of `radius` fits into the view of a camera with the given
+/// vertical field of view (in radians) and aspect ratio
+pub fn framing_distance(radius: f32, fov: f32, aspect_ratio: f32, margin: f32) -> f32 {
+    let half_vertical = fov / 2.0;
+    let half_horizontal = (half_vertical.tan() * aspect_ratio).atan();
+    // The narrower of the two decides how far back the camera needs to be
+    radius * margin / half_vertical.min(half_horizontal).sin()
+}
+
+/// The point the camera looks at and how far away from it the camera is
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FocusPose {
+    pub target: Vec3,
+    pub distance: f32,
+    /// Orthographic scale, distance doesn't change the size of things in orthographic mode
+    pub scale: f32,
+}
+
+impl FocusPose {
+    /// Pose framing the box from `min` to `max` with the given projection
+    pub fn framing(
+        min: Vec3,
+        max: Vec3,
+        projection: &PerspectiveProjection,
+        margin: f32,
+    ) -> FocusPose {
+        let radius = ((max - min) * 0.5).length().max(0.001);
+        let distance = framing_distance(radius, projection.fov, projection.aspect_ratio, margin);
+        FocusPose {
+            target: (min + max) * 0.5,
+            // Don't let the near plane cut into the object
+            distance: distance.max(radius + projection.near),
+            scale: radius * margin / projection.aspect_ratio.min(1.0),
+        }
+    }
+
+    pub fn lerp(&self, to: &FocusPose, t: f32) -> FocusPose {
+        FocusPose {
+            target: self.target.lerp(to.target, t),
+            distance: self.distance + (to.distance - self.distance) * t,
+            scale: self.scale + (to.scale - self.scale) * t,
+        }
+    }
+
+    /// Moves the camera to look at the target from `distance`, keeping the way it's facing
+    pub fn apply(
+        &self,
+        view: &mut CameraView,
+        position: &mut CameraPosition,
+        lens: &mut CameraLens,
+        orbit: Option<&mut CameraOrbit>,
+    ) {
+        if lens.is_orthographic() {
+            lens.scale = self.scale;
+        }
+        match orbit {
+            Some(orbit) => {
+                orbit.target = self.target;
+                orbit.distance = self.distance;
+                orbit.apply(view, position);
+            }
+            None => *position = (self.target - view.front * self.distance).into(),
+        }
+    }
+}
+
+/// Camera flight towards a framed object
+#[derive(Default)]
+pub struct CameraFocusState {
+    focus_events: EventReader<FocusCamera>,
+    flight: Option<(FocusPose, FocusPose)>,
+    elapsed: f32,
+}
+
+fn focus_key_system(
+    config: Res<CameraFocusConfig>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut focus_events: ResMut<Events<FocusCamera>>,
+) {
+    if keyboard_input.just_pressed(config.key) {
+        focus_events.send(FocusCamera { target: None });
+    }
+}
+
+/// World space bounds of the meshes of `entity` and its descendants
+fn entity_bounds(
+    entity: Entity,
+    mesh_bounds: &mut MeshBounds,
+    meshes: &Assets<Mesh>,
+    mesh_query: &Query<(Entity, &Handle<Mesh>, &Transform)>,
+    children_query: &Query<&Children>,
+) -> Option<(Vec3, Vec3)> {
+    let mut bounds = None;
+    let mut stack = vec![entity];
+    while let Some(entity) = stack.pop() {
+        if let (Ok(handle), Ok(transform)) = (
+            mesh_query.get::<Handle<Mesh>>(entity),
+            mesh_query.get::<Transform>(entity),
+        ) {
+            if let Some(aabb) = mesh_bounds.world_aabb(*handle, meshes, &transform) {
+                bounds = Some(bounds.map_or(aabb, |bounds| union_aabb(bounds, aabb)));
+            }
+        }
+        if let Ok(children) = children_query.get::<Children>(entity) {
+            stack.extend(children.iter().copied());
+        }
+    }
+    bounds
+}
+
+fn focus_system(
+    time: Res<Time>,
+    config: Res<CameraFocusConfig>,
+    focus_events: Res<Events<FocusCamera>>,
+    meshes: Res<Assets<Mesh>>,
+    active_camera: Res<ActiveCamera>,
+    mut mesh_bounds: ResMut<MeshBounds>,
+    mut state: ResMut<CameraFocusState>,
+    mesh_query: Query<(Entity, &Handle<Mesh>, &Transform)>,
+    mut content_query: Query<(&Handle<Mesh>, &Transform, Option<&FocusIgnore>)>,
+    children_query: Query<&Children>,
+    mut camera_query: Query<(
+        Entity,
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraMotion,
+        &mut CameraLens,
+        &PerspectiveProjection,
+        Option<&mut CameraOrbit>,
+    )>,
+) {
+    let mut request = None;
+    for event in state.focus_events.iter(&focus_events) {
+        request = Some(*event);
+    }
+
+    let bounds = match request {
+        Some(FocusCamera {
+            target: Some(target),
+        }) => entity_bounds(
+            target,
+            &mut mesh_bounds,
+            &meshes,
+            &mesh_query,
+            &children_query,
+        ),
+        Some(FocusCamera { target: None }) => {
+            let mut bounds = None;
+            for (handle, transform, ignore) in &mut content_query.iter() {
+                if ignore.is_some() {
+                    continue;
+                }
+                if let Some(aabb) = mesh_bounds.world_aabb(*handle, &meshes, &transform) {
+                    bounds = Some(bounds.map_or(aabb, |bounds| union_aabb(bounds, aabb)));
+                }
+            }
+            bounds
+        }
+        None => None,
+    };
+    for (entity, mut view, mut position, mut motion, mut lens, projection, mut orbit) in
+        &mut camera_query.iter()
+    {
+        if !active_camera.is_active(entity) {
+            continue;
+        }
+        if let Some((min, max)) = bounds {
+            let to = FocusPose::framing(min, max, projection, config.margin);
+            // Start from a pose at the same distance in front of the camera, so only the
+            // target moves in a straight line and the camera keeps facing the same way
+            let from = match &orbit {
+                Some(orbit) => FocusPose {
+                    target: orbit.target,
+                    distance: orbit.distance,
+                    scale: lens.scale,
+                },
+                None => FocusPose {
+                    target: position.pos() + view.front * to.distance,
+                    distance: to.distance,
+                    scale: lens.scale,
+                },
+            };
+            state.flight = Some((from, to));
+            state.elapsed = 0.0;
+        }
+
+        let (from, to) = match state.flight {
+            Some(flight) => flight,
+            None => continue,
+        };
+        state.elapsed += time.delta_seconds;
+        let t = if config.duration > 0.0 {
+            (state.elapsed / config.duration).min(1.0)
+        } else {
+            1.0
+        };
+        // Ease in and out
+        let t = t * t * (3.0 - 2.0 * t);
+        from.lerp(&to, t)
+            .apply(&mut view, &mut position, &mut lens, orbit.as_deref_mut());
+        // Keep smoothing from pulling the camera back
+        *motion = CameraMotion::new(&view);
+        if t >= 1.0 {
+            state.flight = None;
+        }
+    }
+}
+
+pub struct CameraFocusPlugin {
+    pub config: CameraFocusConfig,
+}
+
+impl Plugin for CameraFocusPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<FocusCamera>()
+            .init_resource::<CameraFocusState>()
+            .add_resource(self.config)
+            .add_system(focus_key_system.system())
+            .add_system(focus_system.system());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::vec3;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.0001,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    fn projection(aspect_ratio: f32) -> PerspectiveProjection {
+        PerspectiveProjection {
+            fov: FRAC_PI_2,
+            aspect_ratio,
+            near: 1.0,
+            far: 1000.0,
+        }
+    }
+
+    #[test]
+    fn framing_distance_fits_the_sphere_into_the_narrower_side() {
+        let sqrt_2 = 2.0f32.sqrt();
+        // 45 degrees to either side, the sphere touches the edges of the view
+        assert_near(framing_distance(1.0, FRAC_PI_2, 1.0, 1.0), sqrt_2);
+        // Wide views are limited by their height
+        assert_near(framing_distance(1.0, FRAC_PI_2, 2.0, 1.0), sqrt_2);
+        // Tall views are limited by their width, tan(half horizontal fov) = 0.5
+        assert_near(framing_distance(1.0, FRAC_PI_2, 0.5, 1.0), 5.0f32.sqrt());
+        assert_near(
+            framing_distance(2.0, FRAC_PI_2, 1.0, 1.5),
+            2.0 * 1.5 * sqrt_2,
+        );
+    }
+
+    #[test]
+    fn framing_centers_on_the_box() {
+        let pose = FocusPose::framing(
+            vec3(1.0, 0.0, -2.0),
+            vec3(3.0, 2.0, 0.0),
+            &projection(1.0),
+            1.0,
+        );
+        assert_eq!(pose.target, vec3(2.0, 1.0, -1.0));
+        assert_near(
+            pose.distance,
+            framing_distance(3.0f32.sqrt(), FRAC_PI_2, 1.0, 1.0),
+        );
+    }
+
+    #[test]
+    fn framing_keeps_the_near_plane_out_of_the_object() {
+        let mut projection = projection(1.0);
+        projection.near = 10.0;
+        let pose = FocusPose::framing(Vec3::splat(-1.0), Vec3::splat(1.0), &projection, 1.0);
+        assert_near(pose.distance, 3.0f32.sqrt() + 10.0);
+    }
+
+    #[test]
+    fn ortho_framing_fits_both_sides() {
+        let radius = 3.0f32.sqrt();
+        let (min, max) = (Vec3::splat(-1.0), Vec3::splat(1.0));
+        for aspect_ratio in [0.5, 1.0, 2.0].iter() {
+            let scale = FocusPose::framing(min, max, &projection(*aspect_ratio), 1.1).scale;
+            // scale is half the height, half the width is scale * aspect ratio
+            let narrower = scale.min(scale * aspect_ratio);
+            assert_near(narrower, radius * 1.1);
+        }
+    }
+
+    #[test]
+    fn union_aabb_contains_both() {
+        let union = union_aabb(
+            (vec3(0.0, -1.0, 2.0), vec3(1.0, 0.0, 3.0)),
+            (vec3(-2.0, 0.5, 2.5), vec3(0.5, 4.0, 2.6)),
+        );
+        assert_eq!(union, (vec3(-2.0, -1.0, 2.0), vec3(1.0, 4.0, 3.0)));
+    }
+}
@@ -0,0 +1,202 @@
+use super::{
+    camera_motion::CameraMotion, camera_position::CameraPosition, camera_view::CameraView,
+};
+use bevy::prelude::*;
+
+/// Makes the camera trail the `target` entity, i.e. a third person chase camera.
+/// While following, the camera ignores keyboard, mouse and gamepad input and always looks at
+/// the target.
+#[derive(Clone, Copy, Debug)]
+pub struct CameraFollow {
+    pub target: Entity,
+    /// Where the camera is relative to the target, in the target's frame, i.e. rotating
+    /// the target (or its parent) swings the camera around with it. Scale of the target
+    /// doesn't apply.
+    pub offset: Vec3,
+    /// Seconds the camera takes to cover most of the way to where it should be, higher
+    /// values trail further behind, 0 sticks to the target
+    pub lag: f32,
+    /// Smoothed point the camera looks at
+    look_at: Option<Vec3>,
+}
+
+impl CameraFollow {
+    pub fn new(target: Entity, offset: Vec3, lag: f32) -> Self {
+        CameraFollow {
+            target,
+            offset,
+            lag,
+            look_at: None,
+        }
+    }
+}
+
+/// Fraction of the remaining way covered in `dt` seconds when trailing with `lag`.
+/// Exponential, so the camera moves the same way regardless of the frame rate.
+pub fn follow_factor(lag: f32, dt: f32) -> f32 {
+    if lag <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-dt / lag).exp()
+    }
+}
+
+/// Point at `offset` in the frame of a target with the world transform `target`, ignoring
+/// the target's scale
+pub fn chase_point(target: &Mat4, offset: Vec3) -> Vec3 {
+    let axis = |axis: Vec4| {
+        let axis = axis.truncate();
+        if axis.length_squared() > 0.0 {
+            axis.normalize()
+        } else {
+            axis
+        }
+    };
+    target.w_axis().truncate()
+        + axis(target.x_axis()) * offset.x()
+        + axis(target.y_axis()) * offset.y()
+        + axis(target.z_axis()) * offset.z()
+}
+
+/// Yaw and pitch in degrees of a camera at `from` looking at `to`,
+/// `None` if both are at the same point
+pub fn look_at_angles(from: Vec3, to: Vec3) -> Option<(f32, f32)> {
+    let direction = to - from;
+    if direction.length_squared() < 1e-8 {
+        return None;
+    }
+    let direction = direction.normalize();
+    let yaw = direction.z().atan2(direction.x()).to_degrees();
+    let pitch = direction.y().max(-1.0).min(1.0).asin().to_degrees();
+    Some((yaw, pitch))
+}
+
+pub(crate) fn camera_follow_system(
+    time: Res<Time>,
+    mut camera_query: Query<(
+        &mut CameraFollow,
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraMotion,
+    )>,
+    target_query: Query<&Transform>,
+) {
+    for (mut follow, mut view, mut position, mut motion) in &mut camera_query.iter() {
+        // World transform, so children of i.e. a glTF scene are followed where they're drawn
+        let target = match target_query.get::<Transform>(follow.target) {
+            Ok(transform) => transform.value,
+            Err(_) => continue,
+        };
+        let translation = target.w_axis().truncate();
+        let t = follow_factor(follow.lag, time.delta_seconds);
+        let desired = chase_point(&target, follow.offset);
+        let pos = position.pos().lerp(desired, t);
+        let look_at = follow
+            .look_at
+            .map_or(translation, |look_at| look_at.lerp(translation, t));
+        follow.look_at = Some(look_at);
+
+        if let Some((yaw, pitch)) = look_at_angles(pos, look_at) {
+            view.yaw = yaw;
+            view.pitch = pitch;
+            view.roll = 0.0;
+            view.update_camera_vectors();
+        }
+        *position = pos.into();
+        // Keep smoothing from pulling the camera away from the target
+        *motion = CameraMotion::new(&view);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::camera::camera_view::CameraViewOpts;
+    use bevy::math::vec3;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.0001,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    fn assert_near_vec(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual - expected).length() < 0.0001,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn no_lag_sticks_to_the_target() {
+        assert_eq!(follow_factor(0.0, 0.016), 1.0);
+        assert_eq!(follow_factor(-1.0, 0.016), 1.0);
+        assert_eq!(follow_factor(0.5, 0.0), 0.0);
+    }
+
+    #[test]
+    fn lag_is_frame_rate_independent() {
+        let lag = 0.3;
+        // Remaining way after a 30 fps frame and after two 60 fps frames
+        let at_30 = 1.0 - follow_factor(lag, 1.0 / 30.0);
+        let at_60 = (1.0 - follow_factor(lag, 1.0 / 60.0)).powi(2);
+        assert_near(at_30, at_60);
+        // After one lag most of the way is covered
+        assert_near(follow_factor(lag, lag), 1.0 - (-1.0f32).exp());
+        assert!(follow_factor(1.0, 0.016) < follow_factor(0.1, 0.016));
+    }
+
+    #[test]
+    fn look_at_angles_of_the_same_point_are_undefined() {
+        assert_eq!(look_at_angles(Vec3::one(), Vec3::one()), None);
+        assert_eq!(
+            look_at_angles(Vec3::one(), Vec3::one() + Vec3::splat(1e-5)),
+            None
+        );
+    }
+
+    #[test]
+    fn look_at_angles_match_the_view_convention() {
+        let (yaw, pitch) = look_at_angles(Vec3::zero(), vec3(0.0, 0.0, -3.0)).unwrap();
+        assert_near(yaw, -90.0);
+        assert_near(pitch, 0.0);
+        let (yaw, pitch) = look_at_angles(Vec3::zero(), vec3(1.0, 1.0, 0.0)).unwrap();
+        assert_near(yaw, 0.0);
+        assert_near(pitch, 45.0);
+
+        // A view with those angles faces the point
+        let from = vec3(1.0, 2.0, 3.0);
+        let to = vec3(-4.0, 0.5, 7.0);
+        let (yaw, pitch) = look_at_angles(from, to).unwrap();
+        let view = CameraView::new(&CameraViewOpts {
+            yaw,
+            pitch,
+            roll: 0.0,
+        });
+        assert_near_vec(view.front, (to - from).normalize());
+    }
+
+    #[test]
+    fn chase_point_is_in_the_targets_world_frame() {
+        let offset = vec3(0.0, 2.0, 5.0);
+        assert_near_vec(chase_point(&Mat4::identity(), offset), offset);
+
+        // Parent turned a quarter to the left around y, child moved along the parent's x
+        let parent =
+            Mat4::from_rotation_translation(Quat::from_rotation_y(FRAC_PI_2), vec3(10.0, 0.0, 0.0));
+        let child = parent * Mat4::from_translation(vec3(1.0, 0.0, 0.0));
+        assert_near_vec(child.w_axis().truncate(), vec3(10.0, 0.0, -1.0));
+        // Behind the child is +x in world space
+        assert_near_vec(chase_point(&child, offset), vec3(15.0, 2.0, -1.0));
+
+        // Scale doesn't push the camera further away
+        let scaled = child * Mat4::from_scale(Vec3::splat(3.0));
+        assert_near_vec(chase_point(&scaled, offset), vec3(15.0, 2.0, -1.0));
+    }
+}
@@ -0,0 +1,384 @@
+use super::{
+    camera_config::CameraConfig,
+    camera_follow::CameraFollow,
+    camera_motion::{delta_millis, CameraMotion},
+    camera_orbit::CameraOrbit,
+    camera_position::{CameraMovement, CameraPosition},
+    camera_switch::ActiveCamera,
+    camera_view::CameraView,
+};
+use bevy::{
+    input::gamepad::{
+        Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, GamepadEvent,
+        GamepadEventType,
+    },
+    math::vec3,
+    prelude::*,
+};
+
+//
+// Gamepad input.
+//
+// Only built with the `gamepad` feature. Bevy 0.1 has no gamepad backend and sends no
+// gamepad events, its gamepad input and the gilrs plugin feeding it arrived in bevy 0.2.
+//
+// gamepad_input_system copies the state of the first connected gamepad into the
+// CameraGamepadInput resource, other input sources, i.e. a touch overlay, can write to it
+// as well while no gamepad is connected. Mapping that state to camera motion is done by
+// gamepad_motion, which doesn't depend on anything but its arguments.
+//
+
+/// Current state of the gamepad driving the camera.
+/// Stick axes range from -1 to 1 with positive values pointing right and up,
+/// trigger axes range from 0 (released) to 1 (fully pressed).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CameraGamepadInput {
+    /// Moves forward/backward and left/right
+    pub left_stick: Vec2,
+    /// Looks around
+    pub right_stick: Vec2,
+    /// Moves down
+    pub left_trigger: f32,
+    /// Moves up
+    pub right_trigger: f32,
+    /// Zooms out while held
+    pub left_shoulder: bool,
+    /// Zooms in while held
+    pub right_shoulder: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CameraGamepadConfig {
+    /// Stick deflection below which the stick is considered centered
+    pub stick_deadzone: f32,
+    /// Trigger travel below which the trigger is considered released
+    pub trigger_deadzone: f32,
+    /// Multiplies `CameraConfig::mov_speed` at full deflection
+    pub move_sensitivity: f32,
+    /// Degrees per millisecond the camera turns at full deflection
+    pub look_sensitivity: f32,
+    /// Degrees of zoom per millisecond while a shoulder button is held
+    pub zoom_speed: f32,
+}
+
+impl Default for CameraGamepadConfig {
+    fn default() -> Self {
+        CameraGamepadConfig {
+            stick_deadzone: 0.15,
+            trigger_deadzone: 0.05,
+            move_sensitivity: 1.0,
+            look_sensitivity: 0.12,
+            zoom_speed: 0.03,
+        }
+    }
+}
+
+/// Camera motion requested by the gamepad during one frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraGamepadMotion {
+    /// Deflection along the camera's right (x), up (y) and front (z) axes, from -1 to 1
+    pub movement: Vec3,
+    /// Change of yaw and pitch in degrees
+    pub look: Vec2,
+    /// Change of zoom in degrees, positive values zoom in
+    pub zoom: f32,
+}
+
+impl CameraGamepadMotion {
+    pub fn is_idle(&self) -> bool {
+        self.movement == Vec3::zero() && self.look == Vec2::zero() && self.zoom == 0.0
+    }
+}
+
+/// Radial deadzone which rescales the remaining range, so the output still starts at 0
+/// right outside of the deadzone and reaches 1 at full deflection.
+pub fn apply_stick_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone || length == 0.0 {
+        return Vec2::zero();
+    }
+    let scaled = ((length - deadzone) / (1.0 - deadzone)).min(1.0);
+    stick / length * scaled
+}
+
+pub fn apply_trigger_deadzone(trigger: f32, deadzone: f32) -> f32 {
+    if trigger <= deadzone {
+        0.0
+    } else {
+        ((trigger - deadzone) / (1.0 - deadzone)).min(1.0)
+    }
+}
+
+/// Gamepads in the order they were connected, the first one drives the camera
+#[derive(Default)]
+pub(crate) struct CameraGamepads {
+    connected: Vec<Gamepad>,
+    events: EventReader<GamepadEvent>,
+}
+
+pub(crate) fn gamepad_input_system(
+    mut gamepads: ResMut<CameraGamepads>,
+    gamepad_events: Res<Events<GamepadEvent>>,
+    axes: Res<Axis<GamepadAxis>>,
+    button_axes: Res<Axis<GamepadButton>>,
+    buttons: Res<Input<GamepadButton>>,
+    mut input: ResMut<CameraGamepadInput>,
+) {
+    let gamepads = &mut *gamepads;
+    for GamepadEvent(gamepad, event_type) in gamepads.events.iter(&gamepad_events) {
+        match event_type {
+            GamepadEventType::Connected => gamepads.connected.push(*gamepad),
+            GamepadEventType::Disconnected => {
+                gamepads.connected.retain(|connected| connected != gamepad);
+                // Otherwise the camera keeps moving with the last state of the gamepad
+                *input = CameraGamepadInput::default();
+            }
+        }
+    }
+    let gamepad = match gamepads.connected.first() {
+        Some(gamepad) => *gamepad,
+        None => return,
+    };
+
+    let axis = |axis_type| axes.get(&GamepadAxis(gamepad, axis_type)).unwrap_or(0.0);
+    // Triggers are analog buttons
+    let trigger = |button_type| {
+        button_axes
+            .get(&GamepadButton(gamepad, button_type))
+            .unwrap_or(0.0)
+    };
+    let pressed = |button_type| buttons.pressed(GamepadButton(gamepad, button_type));
+    *input = CameraGamepadInput {
+        left_stick: Vec2::new(
+            axis(GamepadAxisType::LeftStickX),
+            axis(GamepadAxisType::LeftStickY),
+        ),
+        right_stick: Vec2::new(
+            axis(GamepadAxisType::RightStickX),
+            axis(GamepadAxisType::RightStickY),
+        ),
+        left_trigger: trigger(GamepadButtonType::LeftTrigger2),
+        right_trigger: trigger(GamepadButtonType::RightTrigger2),
+        left_shoulder: pressed(GamepadButtonType::LeftTrigger),
+        right_shoulder: pressed(GamepadButtonType::RightTrigger),
+    };
+}
+
+/// Maps the gamepad state to the motion it causes over `dt` milliseconds
+pub fn gamepad_motion(
+    input: &CameraGamepadInput,
+    config: &CameraGamepadConfig,
+    dt: f32,
+) -> CameraGamepadMotion {
+    let left = apply_stick_deadzone(input.left_stick, config.stick_deadzone);
+    let right = apply_stick_deadzone(input.right_stick, config.stick_deadzone);
+    let down = apply_trigger_deadzone(input.left_trigger, config.trigger_deadzone);
+    let up = apply_trigger_deadzone(input.right_trigger, config.trigger_deadzone);
+
+    let mut zoom = 0.0;
+    if input.right_shoulder {
+        zoom += config.zoom_speed * dt;
+    }
+    if input.left_shoulder {
+        zoom -= config.zoom_speed * dt;
+    }
+
+    CameraGamepadMotion {
+        movement: vec3(left.x(), up - down, left.y()),
+        look: right * config.look_sensitivity * dt,
+        zoom,
+    }
+}
+
+pub(crate) fn gamepad_camera_system(
+    time: Res<Time>,
+    input: Res<CameraGamepadInput>,
+    active_camera: Res<ActiveCamera>,
+    mut query: Query<(
+        Entity,
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraMotion,
+        &CameraConfig,
+        Option<&mut CameraOrbit>,
+        Option<&CameraFollow>,
+    )>,
+) {
+    let dt = delta_millis(&time);
+    for (entity, mut camera_view, mut position, mut motion, config, orbit, follow) in
+        &mut query.iter()
+    {
+        if !active_camera.is_active(entity) || follow.is_some() {
+            continue;
+        }
+        let gamepad = gamepad_motion(&input, &config.gamepad, dt);
+        if gamepad.is_idle() {
+            continue;
+        }
+        let speed = config.mov_speed * config.gamepad.move_sensitivity;
+
+        if let Some(mut orbit) = orbit {
+            // Same as keyboard and mouse: the left stick dollies and pans, the right stick
+            // orbits around the target
+            let velocity = speed * dt * orbit.distance * 0.2;
+            let movement = gamepad.movement * velocity;
+            orbit.dolly(movement.z() + gamepad.zoom * orbit.distance * 0.01);
+            orbit.pan(movement.x(), movement.y(), &camera_view);
+            orbit.rotate(gamepad.look.x(), -gamepad.look.y());
+            orbit.apply(&mut camera_view, &mut position);
+            continue;
+        }
+
+        let movement = gamepad.movement;
+        let direction = camera_view.movement_direction(CameraMovement::Right, &config)
+            * movement.x()
+            + camera_view.movement_direction(CameraMovement::Up, &config) * movement.y()
+            + camera_view.movement_direction(CameraMovement::Forward, &config) * movement.z();
+        if config.smoothing.is_some() {
+            // keyboard_motion_system resets the wish velocity every frame, so the gamepad
+            // adds to whatever the keys request
+            motion.wish_velocity += direction * speed;
+            motion
+                .target
+                .process_look(gamepad.look.x(), gamepad.look.y(), &config);
+            motion.target.process_zoom(gamepad.zoom, &config);
+        } else {
+            if direction != Vec3::zero() {
+                position.inc_pos(direction * speed * dt);
+            }
+            if gamepad.look != Vec2::zero() {
+                camera_view.process_look(gamepad.look.x(), gamepad.look.y(), &config);
+            }
+            if gamepad.zoom != 0.0 {
+                camera_view.process_zoom(gamepad.zoom, &config);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 16.0;
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.0001,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn centered_gamepad_is_idle() {
+        let config = CameraGamepadConfig::default();
+        let motion = gamepad_motion(&CameraGamepadInput::default(), &config, DT);
+        assert!(motion.is_idle());
+
+        // Sticks resting slightly off center
+        let input = CameraGamepadInput {
+            left_stick: Vec2::new(0.1, -0.1),
+            right_stick: Vec2::new(0.0, 0.14),
+            left_trigger: 0.05,
+            ..Default::default()
+        };
+        assert!(gamepad_motion(&input, &config, DT).is_idle());
+    }
+
+    #[test]
+    fn stick_deadzone_edges() {
+        let deadzone = 0.2;
+        assert_eq!(
+            apply_stick_deadzone(Vec2::new(deadzone, 0.0), deadzone),
+            Vec2::zero()
+        );
+        let outside = apply_stick_deadzone(Vec2::new(0.0, deadzone + 0.008), deadzone);
+        assert_near(outside.x(), 0.0);
+        assert_near(outside.y(), 0.01);
+        assert_eq!(
+            apply_stick_deadzone(Vec2::new(-1.0, 0.0), deadzone),
+            Vec2::new(-1.0, 0.0)
+        );
+        // Halfway between the deadzone and full deflection
+        assert_near(apply_stick_deadzone(Vec2::new(0.6, 0.0), deadzone).x(), 0.5);
+        // Diagonals of square gates reach past the unit circle
+        assert_near(
+            apply_stick_deadzone(Vec2::new(1.0, 1.0), deadzone).length(),
+            1.0,
+        );
+    }
+
+    #[test]
+    fn trigger_deadzone_edges() {
+        let deadzone = 0.1;
+        assert_eq!(apply_trigger_deadzone(0.0, deadzone), 0.0);
+        assert_eq!(apply_trigger_deadzone(deadzone, deadzone), 0.0);
+        assert_near(apply_trigger_deadzone(0.55, deadzone), 0.5);
+        assert_eq!(apply_trigger_deadzone(1.0, deadzone), 1.0);
+    }
+
+    #[test]
+    fn sticks_and_triggers_map_to_camera_axes() {
+        let config = CameraGamepadConfig {
+            stick_deadzone: 0.0,
+            trigger_deadzone: 0.0,
+            ..Default::default()
+        };
+        let input = CameraGamepadInput {
+            left_stick: Vec2::new(0.3, 0.4),
+            right_trigger: 1.0,
+            left_trigger: 0.25,
+            ..Default::default()
+        };
+        let motion = gamepad_motion(&input, &config, DT);
+        assert!((motion.movement - vec3(0.3, 0.75, 0.4)).length() < 0.0001);
+        assert_eq!(motion.look, Vec2::zero());
+        assert_eq!(motion.zoom, 0.0);
+    }
+
+    #[test]
+    fn look_and_zoom_scale_with_sensitivity_and_dt() {
+        let config = CameraGamepadConfig {
+            stick_deadzone: 0.0,
+            look_sensitivity: 0.1,
+            zoom_speed: 0.02,
+            ..Default::default()
+        };
+        let input = CameraGamepadInput {
+            right_stick: Vec2::new(0.8, -0.6),
+            right_shoulder: true,
+            ..Default::default()
+        };
+        let motion = gamepad_motion(&input, &config, DT);
+        assert_near(motion.look.x(), 0.08 * DT);
+        assert_near(motion.look.y(), -0.06 * DT);
+        assert_near(motion.zoom, 0.02 * DT);
+
+        let twice = gamepad_motion(&input, &config, DT * 2.0);
+        assert_near(twice.look.x(), motion.look.x() * 2.0);
+        assert_near(twice.zoom, motion.zoom * 2.0);
+
+        let sensitive = CameraGamepadConfig {
+            look_sensitivity: 0.3,
+            ..config
+        };
+        assert_near(
+            gamepad_motion(&input, &sensitive, DT).look.x(),
+            motion.look.x() * 3.0,
+        );
+
+        let zoom_out = CameraGamepadInput {
+            right_shoulder: false,
+            left_shoulder: true,
+            ..input
+        };
+        assert_near(gamepad_motion(&zoom_out, &config, DT).zoom, -0.02 * DT);
+        let both = CameraGamepadInput {
+            left_shoulder: true,
+            ..input
+        };
+        assert_eq!(gamepad_motion(&both, &config, DT).zoom, 0.0);
+    }
+}
@@ -1,112 +1,211 @@
-use super::{camera_position::CameraPosition, camera_view::CameraView};
+use super::{
+    camera_culling::CameraCullingStats, camera_motion::delta_millis,
+    camera_position::CameraPosition, camera_switch::ActiveCamera, camera_view::CameraView,
+};
+use crate::libs::frame_time::{FrameTimePlugin, FrameTimeStats};
 use bevy::{prelude::*, render::camera::PerspectiveProjection};
 
+const CAMERA_INFO_FONT: &str = "resources/fonts/DejaVuSansMono.ttf";
+
+/// Marks the UI text that shows the camera info when using `CameraInfoOutput::OnScreen`
+pub struct CameraInfoText;
+
+/// Camera properties included in the info output. Tracked so that we only report camera
+/// info when the camera actually changed since we last reported it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraInfoSnapshot {
+    pub position: Vec3,
+    pub pitch: f32,
+    pub yaw: f32,
+    pub zoom: f32,
+    pub fov: f32,
+}
+
+impl CameraInfoSnapshot {
+    pub fn new(
+        camera_view: &CameraView,
+        camera_position: &CameraPosition,
+        projection: &PerspectiveProjection,
+    ) -> Self {
+        CameraInfoSnapshot {
+            position: camera_position.pos(),
+            pitch: camera_view.pitch,
+            yaw: camera_view.yaw,
+            zoom: camera_view.zoom,
+            fov: projection.fov,
+        }
+    }
+
+    /// Returns `true` if this snapshot differs from the `last` reported one, or if nothing
+    /// was reported yet.
+    pub fn changed_since(&self, last: Option<&CameraInfoSnapshot>) -> bool {
+        match last {
+            Some(last) => self != last,
+            None => true,
+        }
+    }
+}
+
+/// Sent every `CameraInfoConfig::interval_millis` with the info of the active camera
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraInfoReport {
+    pub snapshot: CameraInfoSnapshot,
+    /// `false` if the camera is at rest since the last report, in which case the console
+    /// output only shows the frame time
+    pub changed: bool,
+}
+
 /// Note that this requires the CameraPlugin to be active
 fn update_camera_info(
-    dt: f32,
+    frame_time: &FrameTimeStats,
+    culling: &CameraCullingStats,
     info_config: &CameraInfoConfig,
-    camera_view: &CameraView,
-    camera_position: &CameraPosition,
-    projection: &PerspectiveProjection,
+    info_state: &mut CameraInfoState,
+    windows: &mut Windows,
+    text_query: &mut Query<(&CameraInfoText, &mut Text)>,
+    snapshot: &CameraInfoSnapshot,
+    changed: bool,
 ) {
+    let fps = match frame_time.summary() {
+        Some(summary) => summary.to_string(),
+        None => "FPS: n/a".to_string(),
+    };
     let info = format!(
-        "({:.2}, {:.2}, {:.2}) pitch: {:.2}, yaw: {:.2}, zoom: {:.2}, fov: {:.2}, FPS: {:.0}",
-        camera_position.pos().x(),
-        camera_position.pos().y(),
-        camera_position.pos().z(),
-        camera_view.pitch,
-        camera_view.yaw,
-        camera_view.zoom,
-        projection.fov,
-        (1000.0 / dt).round()
+        "({:.2}, {:.2}, {:.2}) pitch: {:.2}, yaw: {:.2}, zoom: {:.2}, fov: {:.2}, visible: {}, culled: {}, {}",
+        snapshot.position.x(),
+        snapshot.position.y(),
+        snapshot.position.z(),
+        snapshot.pitch,
+        snapshot.yaw,
+        snapshot.zoom,
+        snapshot.fov,
+        culling.visible,
+        culling.culled,
+        fps
     );
     match info_config.output {
         CameraInfoOutput::WindowTitle => {
-            eprintln!("Updating window title not supported yet.");
+            if let Some(window) = windows.get_primary_mut() {
+                // Keep the title the app was started with and append the info to it
+                let title = info_state
+                    .original_title
+                    .get_or_insert_with(|| window.title().to_string());
+                window.set_title(format!("{} | {}", title, info));
+            }
         }
         CameraInfoOutput::Console => {
-            eprintln!("{}", info);
+            // Avoid repeating the same camera info over and over while the camera is at rest
+            if changed {
+                eprintln!("{}", info);
+            } else {
+                eprintln!("{}", fps);
+            }
+        }
+        CameraInfoOutput::OnScreen => {
+            for (_, mut text) in &mut text_query.iter() {
+                text.value = info.clone();
+            }
         }
     };
 }
 
-//
-// A bug in bevy prevents this from working properly.
-//
-// If we change: mut camera_query: Query<(&CameraView, &CameraPosition, &PerspectiveProjection)>,
-//           to: mut camera_query: Query<(Changed<CameraView>, &CameraPosition, &PerspectiveProjection)>,
-// the following happens.
-//
-// 1. we enter the below function body no matter if CameraView was mutated or not
-// 2. `&mut camera_query.iter()` never has any items (even if CameraView was mutated) and
-//    thus we never enter the loop body
-//
-// Interestingly enough this works as expected inside the ./camera_plugin.rs where
-// this plugin is initialized.
-//
-// For now we print camera info on each tick even if nothing about it changed.
-//
-fn on_camera_view_changed(
+fn setup_on_screen_info(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
     info_config: Res<CameraInfoConfig>,
-    mut info_state: ResMut<CameraInfoState>,
-    mut camera_query: Query<(Changed<CameraView>, &CameraPosition, &PerspectiveProjection)>,
 ) {
-    if info_state.millis_since_last_update < info_config.interval_millis {
-        return;
-    }
-    let dt = info_state.millis_since_last_update / info_state.frames_since_last_update;
-
-    for (camera_view, camera_position, camera_projection) in &mut camera_query.iter() {
-        update_camera_info(
-            dt as f32,
-            &info_config,
-            &*camera_view,
-            camera_position,
-            camera_projection,
-        );
+    match info_config.output {
+        CameraInfoOutput::OnScreen => {}
+        _ => return,
     }
-
-    info_state.millis_since_last_update = 0;
-    info_state.frames_since_last_update = 0;
+    let font = asset_server
+        .load(CAMERA_INFO_FONT)
+        .expect("Failed to load camera info font");
+    commands
+        .spawn(UiCameraComponents::default())
+        .spawn(TextComponents {
+            style: Style {
+                align_self: AlignSelf::FlexEnd,
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(5.0),
+                    left: Val::Px(5.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text {
+                value: "".to_string(),
+                font,
+                style: TextStyle {
+                    font_size: 16.0,
+                    color: Color::WHITE,
+                },
+            },
+            ..Default::default()
+        })
+        .with(CameraInfoText);
 }
 
-/* Disabled for now as it only makes sense once @see on_camera_view_changed works
- * as expected.
-fn on_camera_position_changed(
+// Checks the camera against the last reported snapshot instead of relying on Changed<>
+// queries, which don't reliably detect camera changes from within this plugin.
+fn report_camera_info(
     info_config: Res<CameraInfoConfig>,
+    frame_time: Res<FrameTimeStats>,
+    culling: Res<CameraCullingStats>,
     mut info_state: ResMut<CameraInfoState>,
-    mut camera_query: Query<(&CameraView, Changed<CameraPosition>)>,
+    mut reports: ResMut<Events<CameraInfoReport>>,
+    mut windows: ResMut<Windows>,
+    mut text_query: Query<(&CameraInfoText, &mut Text)>,
+    active_camera: Res<ActiveCamera>,
+    mut camera_query: Query<(Entity, &CameraView, &CameraPosition, &PerspectiveProjection)>,
 ) {
-    if info_state.millis_since_last_update < info_config.interval_millis {
+    if info_state.millis_since_last_update < info_config.interval_millis as f32 {
         return;
     }
-    let dt = info_state.millis_since_last_update / info_state.frames_since_last_update;
 
-    for (camera_view, camera_position) in &mut camera_query.iter() {
-        update_camera_info(dt as f32, &info_config, camera_view, &*camera_position);
+    for (entity, camera_view, camera_position, camera_projection) in &mut camera_query.iter() {
+        if !active_camera.is_active(entity) {
+            continue;
+        }
+        let snapshot = CameraInfoSnapshot::new(camera_view, camera_position, camera_projection);
+        let changed = snapshot.changed_since(info_state.last_snapshot.as_ref());
+        update_camera_info(
+            &frame_time,
+            &culling,
+            &info_config,
+            &mut info_state,
+            &mut windows,
+            &mut text_query,
+            &snapshot,
+            changed,
+        );
+        info_state.last_snapshot = Some(snapshot);
+        reports.send(CameraInfoReport { snapshot, changed });
     }
 
-    info_state.millis_since_last_update = 0;
-    info_state.frames_since_last_update = 0;
+    info_state.millis_since_last_update = 0.0;
 }
- */
 
 fn on_tick(time: Res<Time>, mut info_state: ResMut<CameraInfoState>) {
-    let dt = time.delta.as_millis();
-    info_state.millis_since_last_update += dt;
-    info_state.frames_since_last_update += 1;
+    info_state.millis_since_last_update += delta_millis(&time);
 }
 
 #[derive(Default, Debug)]
 pub struct CameraInfoState {
-    millis_since_last_update: u128,
-    frames_since_last_update: u128,
+    millis_since_last_update: f32,
+    original_title: Option<String>,
+    last_snapshot: Option<CameraInfoSnapshot>,
 }
 
 #[derive(Clone, Copy)]
 pub enum CameraInfoOutput {
+    /// Appends the info to the title of the primary window
     WindowTitle,
+    /// Prints the info to stderr
     Console,
+    /// Renders the info as a UI text overlay in the top left corner of the window
+    OnScreen,
 }
 
 #[derive(Clone, Copy)]
@@ -131,9 +230,142 @@ pub struct CameraInfoPlugin {
 
 impl Plugin for CameraInfoPlugin {
     fn build(&self, app: &mut AppBuilder) {
+        // Other plugins may already record frame times, in which case we share their stats
+        if app.resources().get::<FrameTimeStats>().is_none() {
+            app.add_plugin(FrameTimePlugin::default());
+        }
         app.init_resource::<CameraInfoState>()
+            .add_event::<CameraInfoReport>()
             .add_resource(self.config)
+            .add_startup_system(setup_on_screen_info.system())
             .add_system(on_tick.system())
-            .add_system(on_camera_view_changed.system());
+            .add_system(report_camera_info.system());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::vec3;
+
+    struct Headless {
+        world: World,
+        resources: Resources,
+        schedule: Schedule,
+        camera: Entity,
+        reader: EventReader<CameraInfoReport>,
+    }
+
+    impl Headless {
+        fn new() -> Self {
+            let mut world = World::new();
+            let camera = world.spawn((
+                CameraView::default(),
+                CameraPosition::default(),
+                PerspectiveProjection::default(),
+            ));
+            let mut resources = Resources::default();
+            resources.insert(CameraInfoConfig::default());
+            resources.insert(FrameTimeStats::default());
+            resources.insert(CameraCullingStats::default());
+            resources.insert(CameraInfoState::default());
+            resources.insert(Windows::default());
+            resources.insert(ActiveCamera::with_entity(camera));
+            resources.insert(Events::<CameraInfoReport>::default());
+
+            let mut schedule = Schedule::default();
+            schedule.add_stage("update");
+            schedule.add_system_to_stage("update", report_camera_info.system());
+            schedule.initialize(&mut resources);
+            Headless {
+                world,
+                resources,
+                schedule,
+                camera,
+                reader: Default::default(),
+            }
+        }
+
+        /// Runs a frame `millis` after the last one and returns the reports it sent
+        fn frame(&mut self, millis: f32) -> Vec<CameraInfoReport> {
+            self.resources
+                .get_mut::<CameraInfoState>()
+                .unwrap()
+                .millis_since_last_update += millis;
+            self.schedule.run(&mut self.world, &mut self.resources);
+            let events = self.resources.get::<Events<CameraInfoReport>>().unwrap();
+            self.reader.iter(&events).copied().collect()
+        }
+
+        fn changed(&mut self, millis: f32) -> Vec<bool> {
+            self.frame(millis)
+                .iter()
+                .map(|report| report.changed)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn reports_once_per_interval() {
+        let mut headless = Headless::new();
+        assert_eq!(headless.changed(999.0), vec![]);
+        assert_eq!(headless.changed(1.0), vec![true]);
+        assert_eq!(headless.changed(500.0), vec![]);
+        assert_eq!(headless.changed(500.0).len(), 1);
+    }
+
+    #[test]
+    fn at_rest_only_reports_frame_time() {
+        let mut headless = Headless::new();
+        assert_eq!(headless.changed(1000.0), vec![true]);
+        assert_eq!(headless.changed(1000.0), vec![false]);
+        assert_eq!(headless.changed(1000.0), vec![false]);
+    }
+
+    #[test]
+    fn view_change_is_reported() {
+        let mut headless = Headless::new();
+        headless.changed(1000.0);
+
+        let camera = headless.camera;
+        headless.world.get_mut::<CameraView>(camera).unwrap().yaw += 10.0;
+        let reports = headless.frame(1000.0);
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].changed);
+        assert_eq!(reports[0].snapshot.yaw, CameraView::default().yaw + 10.0);
+        assert_eq!(headless.changed(1000.0), vec![false]);
+
+        headless.world.get_mut::<CameraView>(camera).unwrap().zoom = 30.0;
+        assert_eq!(headless.changed(1000.0), vec![true]);
+    }
+
+    #[test]
+    fn position_change_is_reported() {
+        let mut headless = Headless::new();
+        headless.changed(1000.0);
+
+        let camera = headless.camera;
+        *headless.world.get_mut::<CameraPosition>(camera).unwrap() = vec3(1.0, 2.0, 3.0).into();
+        let reports = headless.frame(1000.0);
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].changed);
+        assert_eq!(reports[0].snapshot.position, vec3(1.0, 2.0, 3.0));
+        assert_eq!(headless.changed(1000.0), vec![false]);
+    }
+
+    #[test]
+    fn inactive_cameras_are_not_reported() {
+        let mut headless = Headless::new();
+        headless.world.spawn((
+            CameraView::default(),
+            CameraPosition::from(vec3(5.0, 5.0, 5.0)),
+            PerspectiveProjection::default(),
+        ));
+        let reports = headless.frame(1000.0);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(
+            reports[0].snapshot.position,
+            CameraPosition::default().pos()
+        );
     }
 }
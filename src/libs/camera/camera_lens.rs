@@ -0,0 +1,198 @@
+use super::{
+    camera_bindings::CameraBindings, camera_config::CameraConfig, camera_motion::CameraMotion,
+    camera_orbit::CameraOrbit, camera_switch::ActiveCamera, camera_view::CameraView,
+};
+use bevy::{
+    prelude::*,
+    render::camera::{CameraProjection, PerspectiveProjection},
+};
+
+//
+// Orthographic projection.
+//
+// Every camera keeps its PerspectiveProjection, which bevy keeps the aspect ratio of up to
+// date and which provides fov, near and far. The CameraLens decides whether the projection
+// matrix is computed from it or is an orthographic one covering the same aspect ratio.
+// This way switching at runtime doesn't need to swap projection components.
+//
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProjectionMode {
+    Perspective,
+    Orthographic,
+}
+
+impl Default for ProjectionMode {
+    fn default() -> Self {
+        ProjectionMode::Perspective
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraLens {
+    pub mode: ProjectionMode,
+    /// Half the height of the view in world units while orthographic, the mouse wheel
+    /// changes it instead of the zoom
+    pub scale: f32,
+}
+
+impl CameraLens {
+    /// Lens whose orthographic scale matches the size of objects at `focus_distance` of a
+    /// perspective camera with the given `zoom`, i.e. field of view in degrees
+    pub fn new(mode: ProjectionMode, zoom: f32, focus_distance: f32) -> Self {
+        CameraLens {
+            mode,
+            scale: ortho_scale_from_zoom(zoom, focus_distance),
+        }
+    }
+
+    pub fn is_orthographic(&self) -> bool {
+        self.mode == ProjectionMode::Orthographic
+    }
+
+    pub fn projection_matrix(&self, perspective: &PerspectiveProjection) -> Mat4 {
+        match self.mode {
+            ProjectionMode::Perspective => perspective.get_projection_matrix(),
+            ProjectionMode::Orthographic => {
+                let half_width = self.scale * perspective.aspect_ratio;
+                Mat4::orthographic_rh(
+                    -half_width,
+                    half_width,
+                    -self.scale,
+                    self.scale,
+                    perspective.near,
+                    perspective.far,
+                )
+            }
+        }
+    }
+
+    /// Zooms the orthographic view, positive values zoom in
+    pub fn process_scale(&mut self, dy: f32) {
+        self.scale = (self.scale * (1.0 - dy)).max(0.01).min(1000.0);
+    }
+}
+
+/// Half height of the orthographic view in which objects at `focus_distance` appear as
+/// large as they do with a perspective field of view of `zoom` degrees
+pub fn ortho_scale_from_zoom(zoom: f32, focus_distance: f32) -> f32 {
+    focus_distance * (zoom.to_radians() / 2.0).tan()
+}
+
+/// The inverse of `ortho_scale_from_zoom`
+pub fn zoom_from_ortho_scale(scale: f32, focus_distance: f32) -> f32 {
+    (2.0 * (scale / focus_distance).atan()).to_degrees()
+}
+
+pub(crate) fn projection_toggle_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    bindings: Res<CameraBindings>,
+    active_camera: Res<ActiveCamera>,
+    mut camera_query: Query<(
+        Entity,
+        &mut CameraLens,
+        &mut CameraView,
+        &mut CameraMotion,
+        &CameraConfig,
+        Option<&CameraOrbit>,
+    )>,
+) {
+    if !keyboard_input.just_pressed(bindings.toggle_projection) {
+        return;
+    }
+    for (entity, mut lens, mut view, mut motion, config, orbit) in &mut camera_query.iter() {
+        if !active_camera.is_active(entity) {
+            continue;
+        }
+        // Orbit cameras look at their target, so that's where sizes should stay the same
+        let focus_distance = orbit.map_or(config.focus_distance, |orbit| orbit.distance);
+        match lens.mode {
+            ProjectionMode::Perspective => {
+                lens.scale = ortho_scale_from_zoom(view.zoom, focus_distance);
+                lens.mode = ProjectionMode::Orthographic;
+            }
+            ProjectionMode::Orthographic => {
+                let zoom = zoom_from_ortho_scale(lens.scale, focus_distance)
+                    .max(config.min_zoom)
+                    .min(config.max_zoom);
+                view.zoom = zoom;
+                motion.target.zoom = zoom;
+                lens.mode = ProjectionMode::Perspective;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.001,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn ortho_scale_round_trips_to_zoom() {
+        for zoom in [1.0, 30.0, 45.0, 90.0, 120.0].iter() {
+            for distance in [0.5, 10.0, 250.0].iter() {
+                let scale = ortho_scale_from_zoom(*zoom, *distance);
+                assert_near(zoom_from_ortho_scale(scale, *distance), *zoom);
+            }
+        }
+    }
+
+    #[test]
+    fn ortho_scale_matches_the_perspective_size_at_the_focus_distance() {
+        // A 90 degree view is as high as it is far away
+        assert_near(ortho_scale_from_zoom(90.0, 10.0), 10.0);
+        // Twice as far covers twice as much
+        assert_near(
+            ortho_scale_from_zoom(45.0, 20.0),
+            2.0 * ortho_scale_from_zoom(45.0, 10.0),
+        );
+        let lens = CameraLens::new(ProjectionMode::Orthographic, 90.0, 4.0);
+        assert!(lens.is_orthographic());
+        assert_near(lens.scale, 4.0);
+    }
+
+    #[test]
+    fn process_scale_zooms_and_clamps() {
+        let mut lens = CameraLens::new(ProjectionMode::Orthographic, 90.0, 10.0);
+        lens.process_scale(0.1);
+        assert_near(lens.scale, 9.0);
+        lens.process_scale(-0.1);
+        assert_near(lens.scale, 9.9);
+
+        lens.scale = 0.011;
+        lens.process_scale(0.5);
+        assert_eq!(lens.scale, 0.01);
+        // Scrolling further than the scale reaches doesn't flip the view
+        lens.process_scale(2.0);
+        assert_eq!(lens.scale, 0.01);
+
+        lens.scale = 900.0;
+        lens.process_scale(-0.5);
+        assert_eq!(lens.scale, 1000.0);
+    }
+
+    #[test]
+    fn orthographic_projection_covers_the_aspect_ratio() {
+        let perspective = PerspectiveProjection {
+            aspect_ratio: 2.0,
+            ..Default::default()
+        };
+        let lens = CameraLens {
+            mode: ProjectionMode::Orthographic,
+            scale: 5.0,
+        };
+        let matrix = lens.projection_matrix(&perspective);
+        let corner = matrix.transform_point3(Vec3::new(10.0, 5.0, -perspective.near));
+        assert_near(corner.x(), 1.0);
+        assert_near(corner.y(), 1.0);
+    }
+}
@@ -0,0 +1,232 @@
+use super::camera_view::CameraView;
+use bevy::prelude::*;
+
+const EPSILON: f32 = 0.0001;
+/// Orientations whose dot product is closer to 1 than this are less than 0.2 degrees apart
+const ORIENTATION_EPSILON: f32 = 0.000_001;
+
+/// Configures inertia based camera motion.
+/// All rates are per millisecond, higher values respond faster.
+#[derive(Clone, Copy, Debug)]
+pub struct CameraSmoothing {
+    /// How quickly the camera reaches full speed while a movement key is held
+    pub acceleration: f32,
+    /// How quickly the camera comes to a halt once no movement key is held
+    pub damping: f32,
+    /// How quickly yaw and pitch catch up with the mouse
+    pub look: f32,
+    /// How quickly the zoom catches up with the mouse wheel
+    pub zoom: f32,
+}
+
+impl Default for CameraSmoothing {
+    fn default() -> Self {
+        CameraSmoothing {
+            acceleration: 0.01,
+            damping: 0.008,
+            look: 0.015,
+            zoom: 0.01,
+        }
+    }
+}
+
+/// Motion state of a camera with smoothing enabled.
+/// Input systems update `wish_velocity` and `target` and `step` moves the camera towards them.
+#[derive(Clone, Copy, Debug)]
+pub struct CameraMotion {
+    /// Current velocity in units per millisecond
+    pub velocity: Vec3,
+    /// Velocity requested by the currently held movement keys
+    pub wish_velocity: Vec3,
+    /// Yaw, pitch and zoom the camera view is easing towards
+    pub target: CameraView,
+}
+
+impl CameraMotion {
+    pub fn new(view: &CameraView) -> Self {
+        CameraMotion {
+            velocity: Vec3::zero(),
+            wish_velocity: Vec3::zero(),
+            target: *view,
+        }
+    }
+
+    /// Advances the motion by `dt` milliseconds and returns the updated view and position, or
+    /// `None` if the camera is at rest, in which case nothing needs to be updated.
+    pub fn step(
+        &mut self,
+        view: &CameraView,
+        position: Vec3,
+        smoothing: &CameraSmoothing,
+        dt: f32,
+    ) -> Option<(CameraView, Vec3)> {
+        if self.wish_velocity.length_squared() > EPSILON * EPSILON {
+            let t = blend_factor(smoothing.acceleration, dt);
+            self.velocity += (self.wish_velocity - self.velocity) * t;
+        } else {
+            self.velocity *= (-smoothing.damping * dt).exp();
+        }
+        if self.velocity.length_squared() < EPSILON * EPSILON {
+            self.velocity = Vec3::zero();
+        }
+
+        let look = blend_factor(smoothing.look, dt);
+        let zoom = blend_factor(smoothing.zoom, dt);
+        // Ease the orientation rather than yaw and pitch, so this works the same in 6-DoF mode
+        let orientation = ease_orientation(view.orientation, self.target.orientation, look);
+        let zoom = ease(view.zoom, self.target.zoom, zoom);
+
+        let moving = self.velocity != Vec3::zero();
+        let turning = orientation != view.orientation || zoom != view.zoom;
+        if !moving && !turning {
+            return None;
+        }
+
+        let mut view = *view;
+        view.zoom = zoom;
+        if orientation != view.orientation {
+            view.orientation = orientation;
+            view.sync_from_orientation();
+        }
+        Some((view, position + self.velocity * dt))
+    }
+}
+
+/// Frame time in milliseconds, the unit of `dt` throughout the camera code.
+/// Unlike `Duration::as_millis` it keeps the fraction, which matters at high frame rates.
+pub fn delta_millis(time: &Time) -> f32 {
+    time.delta_seconds * 1000.0
+}
+
+/// Fraction of the remaining distance covered in `dt` when closing in at `rate`.
+/// Exponential, so the result doesn't depend on how `dt` is sliced up into frames.
+fn blend_factor(rate: f32, dt: f32) -> f32 {
+    1.0 - (-rate * dt).exp()
+}
+
+fn ease_orientation(current: Quat, target: Quat, t: f32) -> Quat {
+    if current.dot(target).abs() > 1.0 - ORIENTATION_EPSILON {
+        target
+    } else {
+        current.lerp(target, t).normalize()
+    }
+}
+
+fn ease(current: f32, target: f32, t: f32) -> f32 {
+    let next = current + (target - current) * t;
+    if (target - next).abs() < EPSILON {
+        target
+    } else {
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::vec3;
+
+    const DT: f32 = 16.0;
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual - expected).length() < 0.0001,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn ease_moves_towards_the_target_and_snaps_when_close() {
+        assert_eq!(ease(0.0, 10.0, 0.25), 2.5);
+        assert_eq!(ease(10.0, 0.0, 0.25), 7.5);
+        assert_eq!(ease(0.0, EPSILON, 0.5), EPSILON);
+        assert_eq!(ease(3.0, 3.0, 0.5), 3.0);
+    }
+
+    #[test]
+    fn ease_orientation_moves_towards_the_target_and_snaps_when_close() {
+        let current = Quat::identity();
+        let target = Quat::from_rotation_y(1.0);
+        let eased = ease_orientation(current, target, 0.5);
+        assert!((eased.length() - 1.0).abs() < 0.0001);
+        let (_, angle) = (eased * current.conjugate()).to_axis_angle();
+        assert!(angle > 0.1 && angle < 0.9, "{}", angle);
+
+        assert_eq!(ease_orientation(target, target, 0.5), target);
+        let almost = Quat::from_rotation_y(1.0 + EPSILON);
+        assert_eq!(ease_orientation(almost, target, 0.5), target);
+    }
+
+    #[test]
+    fn step_at_rest_does_nothing() {
+        let view = CameraView::default();
+        let mut motion = CameraMotion::new(&view);
+        let step = motion.step(&view, Vec3::zero(), &CameraSmoothing::default(), DT);
+        assert!(step.is_none());
+    }
+
+    #[test]
+    fn step_accelerates_towards_the_wish_velocity() {
+        let view = CameraView::default();
+        let smoothing = CameraSmoothing::default();
+        let mut motion = CameraMotion::new(&view);
+        motion.wish_velocity = vec3(0.0, 0.0, -0.01);
+
+        let (_, position) = motion.step(&view, Vec3::zero(), &smoothing, DT).unwrap();
+        let expected = motion.wish_velocity * blend_factor(smoothing.acceleration, DT);
+        assert_near(motion.velocity, expected);
+        assert_near(position, expected * DT);
+
+        for _ in 0..1000 {
+            motion.step(&view, Vec3::zero(), &smoothing, DT);
+        }
+        assert_near(motion.velocity, motion.wish_velocity);
+    }
+
+    #[test]
+    fn step_damps_to_rest_independent_of_frame_rate() {
+        let view = CameraView::default();
+        let smoothing = CameraSmoothing::default();
+        let mut once = CameraMotion::new(&view);
+        once.velocity = vec3(0.01, 0.0, 0.0);
+        let mut twice = once;
+
+        once.step(&view, Vec3::zero(), &smoothing, DT);
+        twice.step(&view, Vec3::zero(), &smoothing, DT / 2.0);
+        twice.step(&view, Vec3::zero(), &smoothing, DT / 2.0);
+        assert_near(once.velocity, twice.velocity);
+        assert!(once.velocity.x() < 0.01);
+
+        let mut frames = 0;
+        while once.step(&view, Vec3::zero(), &smoothing, DT).is_some() {
+            frames += 1;
+            assert!(frames < 1000, "never came to rest");
+        }
+        assert_eq!(once.velocity, Vec3::zero());
+    }
+
+    #[test]
+    fn step_eases_view_and_zoom_to_the_target() {
+        let view = CameraView::default();
+        let smoothing = CameraSmoothing::default();
+        let mut motion = CameraMotion::new(&view);
+        motion.target.zoom = 30.0;
+        motion.target.yaw += 45.0;
+        motion.target.update_camera_vectors();
+
+        let (stepped, position) = motion.step(&view, Vec3::zero(), &smoothing, DT).unwrap();
+        assert_eq!(position, Vec3::zero());
+        assert!(stepped.zoom < view.zoom && stepped.zoom > 30.0);
+        assert!(stepped.yaw > view.yaw && stepped.yaw < motion.target.yaw);
+
+        let mut view = stepped;
+        while let Some((stepped, _)) = motion.step(&view, Vec3::zero(), &smoothing, DT) {
+            view = stepped;
+        }
+        assert_eq!(view.zoom, 30.0);
+        assert_eq!(view.orientation, motion.target.orientation);
+        assert!((view.yaw - motion.target.yaw).abs() < 0.01);
+    }
+}
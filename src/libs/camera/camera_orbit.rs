@@ -0,0 +1,238 @@
+use super::{camera_position::CameraPosition, camera_view::CameraView};
+use bevy::{math::vec3, prelude::*};
+
+/// Orbit/arcball state of a camera, i.e. it looks at a `target` from `distance`
+/// away, positioned by `azimuth` (around the world up axis) and `elevation`
+/// (above the horizontal plane), both in degrees.
+#[derive(Clone, Copy, Debug)]
+pub struct CameraOrbit {
+    pub target: Vec3,
+    pub distance: f32,
+    pub azimuth: f32,
+    pub elevation: f32,
+
+    pub min_distance: f32,
+    pub max_distance: f32,
+}
+
+pub struct CameraOrbitOpts {
+    pub target: Vec3,
+    pub distance: f32,
+    pub azimuth: f32,
+    pub elevation: f32,
+}
+
+impl Default for CameraOrbitOpts {
+    fn default() -> Self {
+        Self {
+            target: Vec3::zero(),
+            distance: 3.0,
+            azimuth: 90.0,
+            elevation: 0.0,
+        }
+    }
+}
+
+impl CameraOrbit {
+    pub fn new(opts: &CameraOrbitOpts) -> Self {
+        CameraOrbit {
+            target: opts.target,
+            distance: opts.distance,
+            azimuth: opts.azimuth,
+            elevation: opts.elevation,
+            min_distance: 0.1,
+            max_distance: 1000.0,
+        }
+    }
+
+    pub fn eye(&self) -> Vec3 {
+        orbit_eye(self.target, self.distance, self.azimuth, self.elevation)
+    }
+
+    pub fn get_view(&self, world_up: Vec3) -> Mat4 {
+        orbit_view(
+            self.target,
+            self.distance,
+            self.azimuth,
+            self.elevation,
+            world_up,
+        )
+    }
+
+    pub fn rotate(&mut self, d_azimuth: f32, d_elevation: f32) {
+        self.azimuth += d_azimuth;
+        // Stay clear of the poles where the up vector is parallel to the view direction
+        self.elevation = (self.elevation + d_elevation).max(-89.0).min(89.0);
+    }
+
+    pub fn dolly(&mut self, delta: f32) {
+        self.distance = (self.distance - delta)
+            .max(self.min_distance)
+            .min(self.max_distance);
+    }
+
+    /// Moves the target (and thus the camera with it) along the camera's right and up axes.
+    pub fn pan(&mut self, dx: f32, dy: f32, view: &CameraView) {
+        self.target += view.right * dx + view.up * dy;
+    }
+
+    /// Syncs the free camera representation with this orbit, so that everything that
+    /// works with `CameraView` and `CameraPosition` (transform update, camera info)
+    /// keeps working in orbit mode.
+    pub fn apply(&self, view: &mut CameraView, position: &mut CameraPosition) {
+        // The camera looks back along the direction from the target to the eye.
+        view.yaw = self.azimuth + 180.0;
+        view.pitch = -self.elevation;
+        view.roll = 0.0;
+        view.update_camera_vectors();
+        *position = self.eye().into();
+    }
+
+    /// The inverse of `apply`, i.e. orbits around the point the camera is looking at,
+    /// keeping the current distance.
+    pub fn align_to(&mut self, view: &CameraView, position: &CameraPosition) {
+        self.target = position.pos() + view.front * self.distance;
+        self.azimuth = view.yaw - 180.0;
+        self.elevation = -view.pitch;
+    }
+}
+
+/// Position of the eye orbiting `target` at the given `distance`, `azimuth` and `elevation`.
+pub fn orbit_eye(target: Vec3, distance: f32, azimuth: f32, elevation: f32) -> Vec3 {
+    let (azimuth, elevation) = (azimuth.to_radians(), elevation.to_radians());
+    let dir = vec3(
+        azimuth.cos() * elevation.cos(),
+        elevation.sin(),
+        azimuth.sin() * elevation.cos(),
+    );
+    target + dir * distance
+}
+
+/// View matrix of a camera orbiting `target`, see `orbit_eye`.
+pub fn orbit_view(
+    target: Vec3,
+    distance: f32,
+    azimuth: f32,
+    elevation: f32,
+    world_up: Vec3,
+) -> Mat4 {
+    let eye = orbit_eye(target, distance, azimuth, elevation);
+    Mat4::face_toward(eye, target, world_up)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual - expected).length() < 0.0001,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    fn orbit_at(azimuth: f32, elevation: f32) -> CameraOrbit {
+        CameraOrbit::new(&CameraOrbitOpts {
+            target: vec3(1.0, 2.0, 3.0),
+            distance: 4.0,
+            azimuth,
+            elevation,
+        })
+    }
+
+    #[test]
+    fn eye_follows_azimuth_and_elevation() {
+        let target = vec3(1.0, 2.0, 3.0);
+        assert_near(
+            orbit_eye(target, 4.0, 0.0, 0.0),
+            target + vec3(4.0, 0.0, 0.0),
+        );
+        assert_near(
+            orbit_eye(target, 4.0, 90.0, 0.0),
+            target + vec3(0.0, 0.0, 4.0),
+        );
+        assert_near(
+            orbit_eye(target, 4.0, 180.0, 0.0),
+            target + vec3(-4.0, 0.0, 0.0),
+        );
+        assert_near(
+            orbit_eye(target, 4.0, 0.0, 90.0),
+            target + vec3(0.0, 4.0, 0.0),
+        );
+        assert_near(
+            orbit_eye(target, 4.0, 45.0, -90.0),
+            target + vec3(0.0, -4.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn view_is_at_the_eye_facing_the_target() {
+        let target = vec3(1.0, 2.0, 3.0);
+        for &(azimuth, elevation) in
+            [(0.0, 0.0), (90.0, 30.0), (-135.0, -60.0), (10.0, 89.0)].iter()
+        {
+            let view = orbit_view(target, 4.0, azimuth, elevation, Vec3::unit_y());
+            assert_near(
+                view.transform_point3(Vec3::zero()),
+                orbit_eye(target, 4.0, azimuth, elevation),
+            );
+            assert_near(view.transform_point3(vec3(0.0, 0.0, -4.0)), target);
+        }
+    }
+
+    #[test]
+    fn rotate_stops_short_of_the_poles() {
+        let mut orbit = orbit_at(0.0, 80.0);
+        orbit.rotate(30.0, 20.0);
+        assert_eq!(orbit.azimuth, 30.0);
+        assert_eq!(orbit.elevation, 89.0);
+        orbit.rotate(0.0, -400.0);
+        assert_eq!(orbit.elevation, -89.0);
+
+        // Still well defined that close to the pole
+        let view = orbit.get_view(Vec3::unit_y());
+        assert_near(view.transform_point3(Vec3::zero()), orbit.eye());
+        assert_near(view.transform_point3(vec3(0.0, 0.0, -4.0)), orbit.target);
+    }
+
+    #[test]
+    fn apply_looks_at_the_target_and_align_to_inverts_it() {
+        let orbit = orbit_at(30.0, 45.0);
+        let mut view = CameraView::default();
+        let mut position = CameraPosition::default();
+        orbit.apply(&mut view, &mut position);
+        assert_near(position.pos(), orbit.eye());
+        assert_near(view.front, (orbit.target - orbit.eye()).normalize());
+
+        let mut aligned = orbit_at(0.0, 0.0);
+        aligned.target = Vec3::zero();
+        aligned.align_to(&view, &position);
+        assert_near(aligned.target, orbit.target);
+        assert_near(aligned.eye(), orbit.eye());
+    }
+
+    #[test]
+    fn pan_moves_the_target_in_the_view_plane() {
+        let mut orbit = orbit_at(90.0, 0.0);
+        let mut view = CameraView::default();
+        let mut position = CameraPosition::default();
+        orbit.apply(&mut view, &mut position);
+
+        orbit.pan(1.0, 2.0, &view);
+        assert_near(orbit.target, vec3(2.0, 4.0, 3.0));
+        assert_eq!(orbit.distance, 4.0);
+    }
+
+    #[test]
+    fn dolly_is_clamped() {
+        let mut orbit = orbit_at(0.0, 0.0);
+        orbit.dolly(1.5);
+        assert_eq!(orbit.distance, 2.5);
+        orbit.dolly(10.0);
+        assert_eq!(orbit.distance, orbit.min_distance);
+        orbit.dolly(-5000.0);
+        assert_eq!(orbit.distance, orbit.max_distance);
+    }
+}
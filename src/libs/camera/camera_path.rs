@@ -0,0 +1,416 @@
+use super::{
+    camera_motion::CameraMotion, camera_orbit::CameraOrbit, camera_position::CameraPosition,
+    camera_switch::ActiveCamera, camera_view::CameraView,
+};
+use crate::libs::util::{load_from_tmp, save_to_tmp};
+use bevy::{math::vec3, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+const PATH_FILE: &str = "camera_path.ron";
+
+/// Camera pose at a point in time (in seconds) of a `CameraPath`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CameraKeyframe {
+    pub time: f32,
+    pub position: [f32; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Missing in files saved before roll was supported
+    #[serde(default)]
+    pub roll: f32,
+    pub zoom: f32,
+}
+
+impl CameraKeyframe {
+    pub fn capture(time: f32, view: &CameraView, position: &CameraPosition) -> Self {
+        let pos = position.pos();
+        CameraKeyframe {
+            time,
+            position: [pos.x(), pos.y(), pos.z()],
+            yaw: view.yaw,
+            pitch: view.pitch,
+            roll: view.roll,
+            zoom: view.zoom,
+        }
+    }
+
+    fn pos(&self) -> Vec3 {
+        vec3(self.position[0], self.position[1], self.position[2])
+    }
+}
+
+/// Interpolated camera pose along a `CameraPath`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraPathSample {
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+    pub zoom: f32,
+}
+
+impl CameraPathSample {
+    pub fn apply(&self, view: &mut CameraView, position: &mut CameraPosition) {
+        view.yaw = self.yaw;
+        view.pitch = self.pitch;
+        view.roll = self.roll;
+        view.zoom = self.zoom;
+        view.update_camera_vectors();
+        *position = self.position.into();
+    }
+}
+
+/// Keyframes sorted by time which the camera flies through
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CameraPath {
+    pub keyframes: Vec<CameraKeyframe>,
+}
+
+impl CameraPath {
+    pub fn duration(&self) -> f32 {
+        match (self.keyframes.first(), self.keyframes.last()) {
+            (Some(first), Some(last)) => last.time - first.time,
+            _ => 0.0,
+        }
+    }
+
+    /// Adds a keyframe, keeping the keyframes sorted by time
+    pub fn insert(&mut self, keyframe: CameraKeyframe) {
+        let idx = self
+            .keyframes
+            .iter()
+            .position(|k| k.time > keyframe.time)
+            .unwrap_or_else(|| self.keyframes.len());
+        self.keyframes.insert(idx, keyframe);
+    }
+
+    /// Samples the path at `time` seconds, clamping to the first and last keyframe.
+    /// Returns `None` if the path has no keyframes.
+    pub fn sample(&self, time: f32) -> Option<CameraPathSample> {
+        let keyframes = &self.keyframes;
+        let last = keyframes.len().checked_sub(1)?;
+        if time <= keyframes[0].time || last == 0 {
+            return Some(sample_keyframe(&keyframes[0]));
+        }
+        if time >= keyframes[last].time {
+            return Some(sample_keyframe(&keyframes[last]));
+        }
+
+        // Segment k1 -> k2 containing time, k0 and k3 are its neighbors which are
+        // duplicated at the ends of the path
+        let i = keyframes.iter().rposition(|k| k.time <= time).unwrap_or(0);
+        let (k1, k2) = (&keyframes[i], &keyframes[i + 1]);
+        let k0 = &keyframes[i.saturating_sub(1)];
+        let k3 = &keyframes[(i + 2).min(last)];
+
+        let span = k2.time - k1.time;
+        let t = if span > 0.0 {
+            (time - k1.time) / span
+        } else {
+            1.0
+        };
+        Some(CameraPathSample {
+            position: catmull_rom(k0.pos(), k1.pos(), k2.pos(), k3.pos(), t),
+            yaw: lerp_angle(k1.yaw, k2.yaw, t),
+            pitch: lerp_angle(k1.pitch, k2.pitch, t),
+            roll: lerp_angle(k1.roll, k2.roll, t),
+            zoom: k1.zoom + (k2.zoom - k1.zoom) * t,
+        })
+    }
+
+    pub fn save(&self, feat_id: &str) -> Result<String, Box<dyn Error>> {
+        let ron = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        save_to_tmp(feat_id, PATH_FILE, ron)
+    }
+
+    pub fn load(feat_id: &str) -> Result<(String, Self), Box<dyn Error>> {
+        let (full_path, ron) = load_from_tmp(feat_id, PATH_FILE)?;
+        Ok((full_path, ron::de::from_str(&ron)?))
+    }
+}
+
+fn sample_keyframe(keyframe: &CameraKeyframe) -> CameraPathSample {
+    CameraPathSample {
+        position: keyframe.pos(),
+        yaw: keyframe.yaw,
+        pitch: keyframe.pitch,
+        roll: keyframe.roll,
+        zoom: keyframe.zoom,
+    }
+}
+
+/// Uniform Catmull-Rom spline through `p1` (at `t = 0`) and `p2` (at `t = 1`)
+pub fn catmull_rom(p0: Vec3, p1: Vec3, p2: Vec3, p3: Vec3, t: f32) -> Vec3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
+
+/// Interpolates between two angles in degrees along the shortest way around the circle,
+/// i.e. from 170 to -170 passes through 180 instead of 0.
+pub fn lerp_angle(from: f32, to: f32, t: f32) -> f32 {
+    let mut delta = (to - from) % 360.0;
+    if delta > 180.0 {
+        delta -= 360.0;
+    } else if delta < -180.0 {
+        delta += 360.0;
+    }
+    from + delta * t
+}
+
+#[derive(Clone, Copy)]
+pub struct CameraPathConfig {
+    /// Identifies the feature the path is persisted for, see `libs::util::save_to_tmp`
+    pub feat_id: &'static str,
+    /// Appends the current camera pose as a keyframe to the path
+    pub record: KeyCode,
+    /// Starts/stops flying the camera along the path
+    pub play: KeyCode,
+    /// Removes all keyframes from the path
+    pub clear: KeyCode,
+    /// Seconds between recorded keyframes
+    pub keyframe_interval: f32,
+}
+
+impl Default for CameraPathConfig {
+    fn default() -> Self {
+        CameraPathConfig {
+            feat_id: "camera",
+            record: KeyCode::K,
+            play: KeyCode::Return,
+            clear: KeyCode::Back,
+            keyframe_interval: 2.0,
+        }
+    }
+}
+
+/// Playback state of the `CameraPath` resource
+#[derive(Default)]
+pub struct CameraPathPlayer {
+    pub playing: bool,
+    pub elapsed: f32,
+}
+
+fn load_path(config: Res<CameraPathConfig>, mut path: ResMut<CameraPath>) {
+    // Missing path just means that none was recorded yet
+    if let Ok((full_path, loaded)) = CameraPath::load(config.feat_id) {
+        eprintln!(
+            "loaded camera path with {} keyframe(s) from {}",
+            loaded.keyframes.len(),
+            full_path
+        );
+        *path = loaded;
+    }
+}
+
+fn path_keys_system(
+    config: Res<CameraPathConfig>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut path: ResMut<CameraPath>,
+    mut player: ResMut<CameraPathPlayer>,
+    active_camera: Res<ActiveCamera>,
+    mut camera_query: Query<(Entity, &CameraView, &CameraPosition)>,
+) {
+    if keyboard_input.just_pressed(config.play) {
+        player.playing = !player.playing && !path.keyframes.is_empty();
+        player.elapsed = 0.0;
+    }
+    if keyboard_input.just_pressed(config.clear) {
+        path.keyframes.clear();
+        player.playing = false;
+        // Otherwise the cleared keyframes are loaded again the next time the app starts
+        match path.save(config.feat_id) {
+            Ok(full_path) => eprintln!("cleared camera path in {}", full_path),
+            Err(err) => eprintln!("failed to save camera path: {}", err),
+        }
+    }
+    if !keyboard_input.just_pressed(config.record) {
+        return;
+    }
+    for (entity, view, position) in &mut camera_query.iter() {
+        if !active_camera.is_active(entity) {
+            continue;
+        }
+        let time = match path.keyframes.last() {
+            Some(last) => last.time + config.keyframe_interval,
+            None => 0.0,
+        };
+        path.insert(CameraKeyframe::capture(time, view, position));
+        match path.save(config.feat_id) {
+            Ok(full_path) => eprintln!("saved camera keyframe at {}s to {}", time, full_path),
+            Err(err) => eprintln!("failed to save camera path: {}", err),
+        }
+    }
+}
+
+fn path_playback_system(
+    time: Res<Time>,
+    path: Res<CameraPath>,
+    mut player: ResMut<CameraPathPlayer>,
+    active_camera: Res<ActiveCamera>,
+    mut camera_query: Query<(
+        Entity,
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraMotion,
+        Option<&mut CameraOrbit>,
+    )>,
+) {
+    if !player.playing {
+        return;
+    }
+    player.elapsed += time.delta_seconds;
+    let start = path.keyframes.first().map(|k| k.time).unwrap_or(0.0);
+    let sample = match path.sample(start + player.elapsed) {
+        Some(sample) => sample,
+        None => return,
+    };
+    for (entity, mut view, mut position, mut motion, orbit) in &mut camera_query.iter() {
+        if !active_camera.is_active(entity) {
+            continue;
+        }
+        sample.apply(&mut view, &mut position);
+        // Keep smoothing and orbiting from pulling the camera off the path
+        *motion = CameraMotion::new(&view);
+        if let Some(mut orbit) = orbit {
+            orbit.align_to(&view, &position);
+        }
+    }
+    if player.elapsed >= path.duration() {
+        player.playing = false;
+    }
+}
+
+pub struct CameraPathPlugin {
+    pub config: CameraPathConfig,
+}
+
+impl Plugin for CameraPathPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<CameraPath>()
+            .init_resource::<CameraPathPlayer>()
+            .add_resource(self.config)
+            .add_startup_system(load_path.system())
+            .add_system(path_keys_system.system())
+            .add_system(path_playback_system.system());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyframe(time: f32, position: [f32; 3], yaw: f32) -> CameraKeyframe {
+        CameraKeyframe {
+            time,
+            position,
+            yaw,
+            pitch: -10.0,
+            roll: 0.0,
+            zoom: 45.0 - time,
+        }
+    }
+
+    fn path(keyframes: &[CameraKeyframe]) -> CameraPath {
+        let mut path = CameraPath::default();
+        for keyframe in keyframes {
+            path.insert(*keyframe);
+        }
+        path
+    }
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.0001,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn insert_keeps_keyframes_sorted() {
+        let path = path(&[
+            keyframe(2.0, [0.0; 3], 0.0),
+            keyframe(0.0, [0.0; 3], 0.0),
+            keyframe(1.0, [0.0; 3], 0.0),
+        ]);
+        let times: Vec<f32> = path.keyframes.iter().map(|k| k.time).collect();
+        assert_eq!(times, vec![0.0, 1.0, 2.0]);
+        assert_eq!(path.duration(), 2.0);
+    }
+
+    #[test]
+    fn curve_passes_through_keyframes() {
+        let keyframes = [
+            keyframe(0.0, [0.0, 0.0, 0.0], -90.0),
+            keyframe(1.0, [1.0, 2.0, 0.0], -45.0),
+            keyframe(2.0, [3.0, 2.0, -1.0], 0.0),
+            keyframe(4.0, [3.0, 0.0, -4.0], 90.0),
+        ];
+        let path = path(&keyframes);
+        for keyframe in keyframes.iter() {
+            let sample = path.sample(keyframe.time).unwrap();
+            assert!((sample.position - keyframe.pos()).length() < 0.0001);
+            assert_near(sample.yaw, keyframe.yaw);
+            assert_near(sample.pitch, keyframe.pitch);
+            assert_near(sample.zoom, keyframe.zoom);
+        }
+
+        // Between keyframes the curve is continuous
+        let before = path.sample(0.999).unwrap();
+        let after = path.sample(1.001).unwrap();
+        assert!((before.position - after.position).length() < 0.01);
+    }
+
+    #[test]
+    fn angles_wrap_the_short_way() {
+        assert_near(lerp_angle(350.0, 10.0, 0.5) % 360.0, 0.0);
+        assert_near(lerp_angle(10.0, 350.0, 0.5), 0.0);
+        assert_near(lerp_angle(170.0, -170.0, 0.5), 180.0);
+        assert_near(lerp_angle(0.0, 90.0, 0.5), 45.0);
+
+        let path = path(&[
+            keyframe(0.0, [0.0; 3], 350.0),
+            keyframe(1.0, [0.0; 3], 10.0),
+        ]);
+        for i in 0..=10 {
+            let yaw = path.sample(i as f32 * 0.1).unwrap().yaw;
+            // 360 to 370 is the same as 0 to 10
+            assert!(yaw >= 350.0 - 0.0001 && yaw <= 370.0 + 0.0001, "{}", yaw);
+        }
+    }
+
+    #[test]
+    fn sampling_clamps_to_the_ends() {
+        let first = keyframe(1.0, [1.0, 0.0, 0.0], 0.0);
+        let last = keyframe(3.0, [3.0, 0.0, 0.0], 20.0);
+        let path = path(&[first, keyframe(2.0, [2.0, 1.0, 0.0], 10.0), last]);
+        assert_eq!(path.sample(0.0), Some(sample_keyframe(&first)));
+        assert_eq!(path.sample(1.0), Some(sample_keyframe(&first)));
+        assert_eq!(path.sample(3.0), Some(sample_keyframe(&last)));
+        assert_eq!(path.sample(100.0), Some(sample_keyframe(&last)));
+    }
+
+    #[test]
+    fn one_keyframe_path_stays_put() {
+        let only = keyframe(5.0, [1.0, 2.0, 3.0], 30.0);
+        let path = path(&[only]);
+        assert_eq!(path.duration(), 0.0);
+        for &time in [0.0, 5.0, 10.0].iter() {
+            assert_eq!(path.sample(time), Some(sample_keyframe(&only)));
+        }
+    }
+
+    #[test]
+    fn empty_path_has_no_samples() {
+        let path = CameraPath::default();
+        assert_eq!(path.duration(), 0.0);
+        assert_eq!(path.sample(0.0), None);
+    }
+}
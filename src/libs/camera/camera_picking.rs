@@ -0,0 +1,705 @@
+use super::{
+    camera_bindings::CameraBindings, camera_config::CameraConfig, camera_lens::CameraLens,
+    camera_position::CameraPosition, camera_switch::ActiveCamera, camera_view::CameraView,
+};
+use bevy::{
+    math::vec2,
+    prelude::*,
+    render::{
+        camera::PerspectiveProjection,
+        mesh::{VertexAttribute, VertexAttributeValues},
+        pipeline::PrimitiveTopology,
+    },
+    window::CursorMoved,
+};
+
+const EPSILON: f32 = 0.000_001;
+
+/// Half line starting at `origin`, points on it are `origin + direction * t` for `t >= 0`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// Ray through the point at `ndc` (normalized device coordinates, -1 to 1 from the
+    /// bottom left to the top right of the window) of the camera's near plane
+    pub fn from_camera(
+        ndc: Vec2,
+        view: &CameraView,
+        position: &CameraPosition,
+        projection: &PerspectiveProjection,
+        lens: &CameraLens,
+    ) -> Self {
+        if lens.is_orthographic() {
+            // All rays are parallel, starting from where the point is on the view plane
+            let half_width = lens.scale * projection.aspect_ratio;
+            let origin = position.pos()
+                + view.right * (ndc.x() * half_width)
+                + view.up * (ndc.y() * lens.scale);
+            return Ray {
+                origin,
+                direction: view.front,
+            };
+        }
+        // fov is the vertical field of view, the horizontal one follows from the aspect ratio
+        let half_height = (projection.fov / 2.0).tan();
+        let half_width = half_height * projection.aspect_ratio;
+        let direction =
+            view.front + view.right * (ndc.x() * half_width) + view.up * (ndc.y() * half_height);
+        Ray {
+            origin: position.pos(),
+            direction: direction.normalize(),
+        }
+    }
+
+    pub fn at(&self, t: f32) -> Vec3 {
+        self.origin + self.direction * t
+    }
+
+    /// Transforms the ray by `matrix`.
+    /// The direction isn't normalized, so `t` along the transformed ray hits the same point
+    /// as `t` along the original one.
+    pub fn transform(&self, matrix: &Mat4) -> Self {
+        Ray {
+            origin: matrix.transform_point3(self.origin),
+            direction: matrix.transform_vector3(self.direction),
+        }
+    }
+}
+
+/// Converts a cursor position in pixels from the bottom left of the window, which is how
+/// bevy reports it, to normalized device coordinates
+pub fn ndc_from_cursor(cursor: Vec2, window_size: Vec2) -> Vec2 {
+    vec2(
+        cursor.x() / window_size.x() * 2.0 - 1.0,
+        cursor.y() / window_size.y() * 2.0 - 1.0,
+    )
+}
+
+/// Where a ray hit a shape, `t` is the parameter of the hit along the ray
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    pub t: f32,
+    pub point: Vec3,
+    pub normal: Vec3,
+}
+
+pub fn ray_sphere(ray: &Ray, center: Vec3, radius: f32) -> Option<RayHit> {
+    // Solve |origin + direction * t - center|^2 = radius^2 for t, direction needn't be a unit
+    // vector
+    let oc = ray.origin - center;
+    let a = ray.direction.dot(ray.direction);
+    let b = oc.dot(ray.direction);
+    let c = oc.dot(oc) - radius * radius;
+    let discriminant = b * b - a * c;
+    if a < EPSILON || discriminant < 0.0 {
+        return None;
+    }
+    let sqrt = discriminant.sqrt();
+    // Nearest hit in front of the origin, which is the far one if the origin is inside
+    let t = [(-b - sqrt) / a, (-b + sqrt) / a]
+        .iter()
+        .copied()
+        .find(|t| *t >= 0.0)?;
+    let point = ray.at(t);
+    Some(RayHit {
+        t,
+        point,
+        normal: (point - center).normalize(),
+    })
+}
+
+/// Hit of the plane through `point` with the given `normal`, from either side
+pub fn ray_plane(ray: &Ray, point: Vec3, normal: Vec3) -> Option<RayHit> {
+    let denom = normal.dot(ray.direction);
+    if denom.abs() < EPSILON {
+        return None;
+    }
+    let t = normal.dot(point - ray.origin) / denom;
+    if t < 0.0 {
+        return None;
+    }
+    Some(RayHit {
+        t,
+        point: ray.at(t),
+        normal: if denom > 0.0 { -normal } else { normal },
+    })
+}
+
+/// Slab test against the axis aligned box from `min` to `max`
+pub fn ray_aabb(ray: &Ray, min: Vec3, max: Vec3) -> Option<RayHit> {
+    let origin = [ray.origin.x(), ray.origin.y(), ray.origin.z()];
+    let direction = [ray.direction.x(), ray.direction.y(), ray.direction.z()];
+    let min = [min.x(), min.y(), min.z()];
+    let max = [max.x(), max.y(), max.z()];
+
+    let mut t_near = f32::NEG_INFINITY;
+    let mut t_far = f32::INFINITY;
+    // Axis and side of the face the ray enters the box through
+    let mut near_face = (0, -1.0);
+    let mut far_face = (0, 1.0);
+    for axis in 0..3 {
+        if direction[axis].abs() < EPSILON {
+            // Parallel to the slab, so it has to start between its planes
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t_min = (min[axis] - origin[axis]) / direction[axis];
+        let t_max = (max[axis] - origin[axis]) / direction[axis];
+        let (t0, t1, side) = if t_min < t_max {
+            (t_min, t_max, -1.0)
+        } else {
+            (t_max, t_min, 1.0)
+        };
+        if t0 > t_near {
+            t_near = t0;
+            near_face = (axis, side);
+        }
+        if t1 < t_far {
+            t_far = t1;
+            far_face = (axis, -side);
+        }
+        if t_near > t_far {
+            return None;
+        }
+    }
+    if t_far < 0.0 {
+        return None;
+    }
+    // Origin inside of the box hits where the ray leaves it
+    let (t, (axis, side)) = if t_near >= 0.0 {
+        (t_near, near_face)
+    } else {
+        (t_far, far_face)
+    };
+    let mut normal = [0.0; 3];
+    normal[axis] = side;
+    Some(RayHit {
+        t,
+        point: ray.at(t),
+        normal: Vec3::new(normal[0], normal[1], normal[2]),
+    })
+}
+
+/// Möller-Trumbore intersection, hits both sides of the triangle and returns the normal of
+/// the side facing the ray
+pub fn ray_triangle(ray: &Ray, a: Vec3, b: Vec3, c: Vec3) -> Option<RayHit> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+    let p = ray.direction.cross(edge2);
+    let det = edge1.dot(p);
+    if det.abs() < EPSILON {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let s = ray.origin - a;
+    let u = s.dot(p) * inv_det;
+    if u < 0.0 || u > 1.0 {
+        return None;
+    }
+    let q = s.cross(edge1);
+    let v = ray.direction.dot(q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge2.dot(q) * inv_det;
+    if t < 0.0 {
+        return None;
+    }
+    let normal = edge1.cross(edge2).normalize();
+    Some(RayHit {
+        t,
+        point: ray.at(t),
+        normal: if normal.dot(ray.direction) > 0.0 {
+            -normal
+        } else {
+            normal
+        },
+    })
+}
+
+/// Nearest hit of the triangle list made up of `positions`, indexed by `indices` if present
+pub fn ray_triangles(ray: &Ray, positions: &[[f32; 3]], indices: Option<&[u32]>) -> Option<RayHit> {
+    let vertex = |i: usize| {
+        let idx = match indices {
+            Some(indices) => indices[i] as usize,
+            None => i,
+        };
+        positions.get(idx).map(|p| Vec3::new(p[0], p[1], p[2]))
+    };
+    let count = indices.map_or(positions.len(), |indices| indices.len());
+    let mut nearest: Option<RayHit> = None;
+    for i in (0..count - count % 3).step_by(3) {
+        let (a, b, c) = match (vertex(i), vertex(i + 1), vertex(i + 2)) {
+            (Some(a), Some(b), Some(c)) => (a, b, c),
+            _ => continue,
+        };
+        if let Some(hit) = ray_triangle(ray, a, b, c) {
+            if nearest.map_or(true, |nearest| hit.t < nearest.t) {
+                nearest = Some(hit);
+            }
+        }
+    }
+    nearest
+}
+
+/// Vertex positions of the mesh, `None` if it has none
+pub fn mesh_positions(mesh: &Mesh) -> Option<&[[f32; 3]]> {
+    let attribute = mesh
+        .attributes
+        .iter()
+        .find(|attribute| attribute.name == VertexAttribute::POSITION)?;
+    match &attribute.values {
+        VertexAttributeValues::Float3(positions) => Some(positions),
+        _ => None,
+    }
+}
+
+/// Local space `(min, max)` corners of the box around all vertices of the mesh
+pub fn mesh_aabb(mesh: &Mesh) -> Option<(Vec3, Vec3)> {
+    let positions = mesh_positions(mesh)?;
+    let mut iter = positions.iter().map(|p| Vec3::new(p[0], p[1], p[2]));
+    let first = iter.next()?;
+    Some(iter.fold((first, first), |(min, max), p| (min.min(p), max.max(p))))
+}
+
+/// Nearest hit of the mesh's triangles, only triangle lists can be hit
+pub fn ray_mesh(ray: &Ray, mesh: &Mesh) -> Option<RayHit> {
+    if mesh.primitive_topology != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    // Cheap rejection before testing each triangle
+    let (min, max) = mesh_aabb(mesh)?;
+    ray_aabb(ray, min, max)?;
+    ray_triangles(ray, mesh_positions(mesh)?, mesh.indices.as_deref())
+}
+
+/// Shape an entity is picked by, in the entity's local space, i.e. it is moved, rotated and
+/// scaled along with its `Transform`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pickable {
+    Box {
+        half_extents: Vec3,
+        offset: Vec3,
+    },
+    Sphere {
+        radius: f32,
+        offset: Vec3,
+    },
+    /// Triangles of the entity's `Handle<Mesh>`
+    Mesh,
+}
+
+impl Pickable {
+    /// Hit of the ray, given in local space, with this shape
+    pub fn intersect(&self, ray: &Ray, mesh: Option<&Mesh>) -> Option<RayHit> {
+        match self {
+            Pickable::Box {
+                half_extents,
+                offset,
+            } => ray_aabb(ray, *offset - *half_extents, *offset + *half_extents),
+            Pickable::Sphere { radius, offset } => ray_sphere(ray, *offset, *radius),
+            Pickable::Mesh => ray_mesh(ray, mesh?),
+        }
+    }
+}
+
+/// Hit of the world space ray with the shape of an entity with the given world `transform`
+pub fn pick_entity(
+    ray: &Ray,
+    pickable: &Pickable,
+    transform: &Mat4,
+    mesh: Option<&Mesh>,
+) -> Option<RayHit> {
+    let inverse = transform.inverse();
+    let hit = pickable.intersect(&ray.transform(&inverse), mesh)?;
+    // Normals transform with the inverse transpose to stay perpendicular under non uniform
+    // scale, t is the same in both spaces
+    let normal = inverse
+        .transpose()
+        .transform_vector3(hit.normal)
+        .normalize();
+    Some(RayHit {
+        t: hit.t,
+        point: ray.at(hit.t),
+        normal,
+    })
+}
+
+/// Sent when clicking an entity with a `Pickable` component
+#[derive(Clone, Copy, Debug)]
+pub struct Picked {
+    pub entity: Entity,
+    /// World space point the cursor ray hit the entity at
+    pub point: Vec3,
+    /// World space normal of the surface at `point`
+    pub normal: Vec3,
+}
+
+#[derive(Clone, Copy)]
+pub struct CameraPickingConfig {
+    /// Mouse button that picks the entity under the cursor.
+    /// Doesn't pick if it's the look button and the camera grabs the cursor.
+    pub button: MouseButton,
+}
+
+impl Default for CameraPickingConfig {
+    fn default() -> Self {
+        CameraPickingConfig {
+            button: MouseButton::Left,
+        }
+    }
+}
+
+/// Last known cursor position, in pixels from the bottom left of the window
+#[derive(Default)]
+pub struct CursorState {
+    cursor_moved_events: EventReader<CursorMoved>,
+    pub position: Option<Vec2>,
+    pub window_size: Vec2,
+}
+
+fn track_cursor_system(
+    mut state: ResMut<CursorState>,
+    cursor_moved_events: Res<Events<CursorMoved>>,
+    windows: Res<Windows>,
+) {
+    let mut moved = None;
+    for event in state.cursor_moved_events.iter(&cursor_moved_events) {
+        moved = Some((event.id, event.position));
+    }
+    if let Some((id, position)) = moved {
+        state.position = Some(position);
+        if let Some(window) = windows.get(id) {
+            state.window_size = vec2(window.width() as f32, window.height() as f32);
+        }
+    }
+}
+
+/// Ray from the active camera through the cursor, `None` if the cursor position is unknown
+pub fn cursor_ray(
+    cursor: &CursorState,
+    view: &CameraView,
+    position: &CameraPosition,
+    projection: &PerspectiveProjection,
+    lens: &CameraLens,
+) -> Option<Ray> {
+    let cursor_position = cursor.position?;
+    if cursor.window_size.x() <= 0.0 || cursor.window_size.y() <= 0.0 {
+        return None;
+    }
+    let ndc = ndc_from_cursor(cursor_position, cursor.window_size);
+    Some(Ray::from_camera(ndc, view, position, projection, lens))
+}
+
+/// Ray from the active camera through the cursor, updated every frame.
+/// `None` if the cursor position is unknown.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CursorRay(pub Option<Ray>);
+
+fn cursor_ray_system(
+    cursor: Res<CursorState>,
+    active_camera: Res<ActiveCamera>,
+    mut ray: ResMut<CursorRay>,
+    mut camera_query: Query<(
+        Entity,
+        &CameraView,
+        &CameraPosition,
+        &PerspectiveProjection,
+        &CameraLens,
+    )>,
+) {
+    ray.0 = None;
+    for (entity, view, position, projection, lens) in &mut camera_query.iter() {
+        if active_camera.is_active(entity) {
+            ray.0 = cursor_ray(&cursor, view, position, projection, lens);
+        }
+    }
+}
+
+fn picking_system(
+    config: Res<CameraPickingConfig>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    cursor_ray: Res<CursorRay>,
+    meshes: Res<Assets<Mesh>>,
+    bindings: Res<CameraBindings>,
+    active_camera: Res<ActiveCamera>,
+    mut picked_events: ResMut<Events<Picked>>,
+    mut camera_query: Query<(Entity, &CameraConfig)>,
+    mut pickable_query: Query<(Entity, &Pickable, &Transform, Option<&Handle<Mesh>>)>,
+) {
+    if !mouse_button_input.just_pressed(config.button) {
+        return;
+    }
+    // On cameras that grab the cursor the look button click grabs it, and once it's grabbed
+    // there's no cursor to pick with
+    if config.button == bindings.look {
+        for (entity, camera_config) in &mut camera_query.iter() {
+            if active_camera.is_active(entity) && camera_config.grab_cursor {
+                return;
+            }
+        }
+    }
+    let ray = match cursor_ray.0 {
+        Some(ray) => ray,
+        None => return,
+    };
+
+    let mut nearest: Option<(Entity, RayHit)> = None;
+    for (entity, pickable, transform, mesh) in &mut pickable_query.iter() {
+        let mesh = mesh.and_then(|handle| meshes.get(handle));
+        if let Some(hit) = pick_entity(&ray, pickable, &transform.value, mesh) {
+            if nearest.map_or(true, |(_, nearest)| hit.t < nearest.t) {
+                nearest = Some((entity, hit));
+            }
+        }
+    }
+    if let Some((entity, hit)) = nearest {
+        picked_events.send(Picked {
+            entity,
+            point: hit.point,
+            normal: hit.normal,
+        });
+    }
+}
+
+pub struct CameraPickingPlugin {
+    pub config: CameraPickingConfig,
+}
+
+impl Plugin for CameraPickingPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_event::<Picked>()
+            .init_resource::<CursorState>()
+            .init_resource::<CursorRay>()
+            .add_resource(self.config)
+            .add_system(track_cursor_system.system())
+            .add_system(cursor_ray_system.system())
+            .add_system(picking_system.system());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::camera::camera_lens::ProjectionMode;
+    use bevy::math::vec3;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual - expected).length() < 0.0001,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    fn projection() -> PerspectiveProjection {
+        PerspectiveProjection {
+            fov: FRAC_PI_2,
+            aspect_ratio: 2.0,
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+
+    fn ray(origin: Vec3, direction: Vec3) -> Ray {
+        Ray { origin, direction }
+    }
+
+    #[test]
+    fn perspective_rays_start_at_the_camera() {
+        let view = CameraView::default();
+        let position = CameraPosition::from(vec3(1.0, 2.0, 3.0));
+        let lens = CameraLens::new(ProjectionMode::Perspective, 45.0, 10.0);
+
+        let center = Ray::from_camera(Vec2::zero(), &view, &position, &projection(), &lens);
+        assert_near(center.origin, position.pos());
+        assert_near(center.direction, vec3(0.0, 0.0, -1.0));
+
+        // 90° vertical fov at twice the width
+        let corner = Ray::from_camera(vec2(1.0, 1.0), &view, &position, &projection(), &lens);
+        assert_near(corner.origin, position.pos());
+        assert_near(corner.direction, vec3(2.0, 1.0, -1.0).normalize());
+    }
+
+    #[test]
+    fn orthographic_rays_are_parallel() {
+        let view = CameraView::default();
+        let position = CameraPosition::from(vec3(1.0, 2.0, 3.0));
+        let mut lens = CameraLens::new(ProjectionMode::Orthographic, 45.0, 10.0);
+        lens.scale = 2.0;
+
+        let center = Ray::from_camera(Vec2::zero(), &view, &position, &projection(), &lens);
+        assert_near(center.origin, position.pos());
+        assert_near(center.direction, vec3(0.0, 0.0, -1.0));
+
+        let corner = Ray::from_camera(vec2(1.0, -1.0), &view, &position, &projection(), &lens);
+        assert_near(corner.origin, position.pos() + vec3(4.0, -2.0, 0.0));
+        assert_near(corner.direction, center.direction);
+    }
+
+    #[test]
+    fn cursor_maps_to_ndc() {
+        let size = vec2(800.0, 600.0);
+        assert_eq!(ndc_from_cursor(vec2(400.0, 300.0), size), Vec2::zero());
+        assert_eq!(ndc_from_cursor(Vec2::zero(), size), vec2(-1.0, -1.0));
+        assert_eq!(ndc_from_cursor(size, size), vec2(1.0, 1.0));
+    }
+
+    #[test]
+    fn sphere_hits_and_misses() {
+        let hit = ray_sphere(
+            &ray(vec3(0.0, 0.0, 5.0), -Vec3::unit_z()),
+            Vec3::zero(),
+            1.0,
+        )
+        .unwrap();
+        assert!((hit.t - 4.0).abs() < 0.0001);
+        assert_near(hit.point, vec3(0.0, 0.0, 1.0));
+        assert_near(hit.normal, Vec3::unit_z());
+
+        // Passes by, points away, starts inside
+        assert!(ray_sphere(
+            &ray(vec3(2.0, 0.0, 5.0), -Vec3::unit_z()),
+            Vec3::zero(),
+            1.0
+        )
+        .is_none());
+        assert!(ray_sphere(&ray(vec3(0.0, 0.0, 5.0), Vec3::unit_z()), Vec3::zero(), 1.0).is_none());
+        let inside = ray_sphere(&ray(Vec3::zero(), Vec3::unit_x()), Vec3::zero(), 1.0).unwrap();
+        assert_near(inside.point, Vec3::unit_x());
+    }
+
+    #[test]
+    fn box_hits_from_outside_and_inside() {
+        let (min, max) = (Vec3::splat(-1.0), Vec3::splat(1.0));
+        let outside = ray_aabb(&ray(vec3(0.5, 0.0, 5.0), -Vec3::unit_z()), min, max).unwrap();
+        assert!((outside.t - 4.0).abs() < 0.0001);
+        assert_near(outside.point, vec3(0.5, 0.0, 1.0));
+        assert_near(outside.normal, Vec3::unit_z());
+
+        let angled = ray_aabb(&ray(vec3(-3.0, 0.0, 0.0), vec3(1.0, 0.25, 0.0)), min, max).unwrap();
+        assert_near(angled.point, vec3(-1.0, 0.5, 0.0));
+        assert_near(angled.normal, -Vec3::unit_x());
+
+        // Starting inside hits where the ray leaves the box
+        let inside = ray_aabb(&ray(vec3(0.0, 0.0, 0.5), Vec3::unit_x()), min, max).unwrap();
+        assert!((inside.t - 1.0).abs() < 0.0001);
+        assert_near(inside.point, vec3(1.0, 0.0, 0.5));
+        assert_near(inside.normal, Vec3::unit_x());
+
+        assert!(ray_aabb(&ray(vec3(2.0, 0.0, 5.0), -Vec3::unit_z()), min, max).is_none());
+        assert!(ray_aabb(&ray(vec3(0.0, 0.0, 5.0), Vec3::unit_z()), min, max).is_none());
+        // Parallel to a slab and outside of it
+        assert!(ray_aabb(&ray(vec3(0.0, 2.0, 5.0), -Vec3::unit_z()), min, max).is_none());
+    }
+
+    #[test]
+    fn plane_hits_from_both_sides() {
+        let above = ray_plane(
+            &ray(vec3(1.0, 2.0, 0.0), -Vec3::unit_y()),
+            Vec3::zero(),
+            Vec3::unit_y(),
+        )
+        .unwrap();
+        assert!((above.t - 2.0).abs() < 0.0001);
+        assert_near(above.point, vec3(1.0, 0.0, 0.0));
+        assert_near(above.normal, Vec3::unit_y());
+
+        let below = ray_plane(
+            &ray(vec3(1.0, -2.0, 0.0), Vec3::unit_y()),
+            Vec3::zero(),
+            Vec3::unit_y(),
+        )
+        .unwrap();
+        assert_near(below.normal, -Vec3::unit_y());
+    }
+
+    #[test]
+    fn plane_misses_parallel_and_behind_rays() {
+        let normal = Vec3::unit_y();
+        assert!(ray_plane(
+            &ray(vec3(0.0, 1.0, 0.0), Vec3::unit_x()),
+            Vec3::zero(),
+            normal
+        )
+        .is_none());
+        assert!(ray_plane(
+            &ray(vec3(0.0, 1.0, 0.0), Vec3::unit_y()),
+            Vec3::zero(),
+            normal
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn triangle_hit_faces_the_ray() {
+        let (a, b, c) = (
+            vec3(-1.0, -1.0, 0.0),
+            vec3(1.0, -1.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        );
+        let front = ray_triangle(&ray(vec3(0.0, 0.0, 5.0), -Vec3::unit_z()), a, b, c).unwrap();
+        assert!((front.t - 5.0).abs() < 0.0001);
+        assert_near(front.point, Vec3::zero());
+        assert_near(front.normal, Vec3::unit_z());
+
+        let back = ray_triangle(&ray(vec3(0.0, 0.0, -5.0), Vec3::unit_z()), a, b, c).unwrap();
+        assert_near(back.normal, -Vec3::unit_z());
+
+        assert!(ray_triangle(&ray(vec3(0.9, 0.9, 5.0), -Vec3::unit_z()), a, b, c).is_none());
+        assert!(ray_triangle(&ray(vec3(0.0, 0.0, 5.0), Vec3::unit_x()), a, b, c).is_none());
+    }
+
+    #[test]
+    fn nearest_triangle_wins() {
+        let positions = [
+            [-1.0, -1.0, 0.0],
+            [1.0, -1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [-1.0, -1.0, 1.0],
+            [1.0, -1.0, 1.0],
+            [0.0, 1.0, 1.0],
+        ];
+        let down = ray(vec3(0.0, 0.0, 5.0), -Vec3::unit_z());
+        let hit = ray_triangles(&down, &positions, None).unwrap();
+        assert_near(hit.point, Vec3::unit_z());
+        let hit = ray_triangles(&down, &positions, Some(&[0, 1, 2][..])).unwrap();
+        assert_near(hit.point, Vec3::zero());
+    }
+
+    #[test]
+    fn picks_in_the_entity_space() {
+        let pickable = Pickable::Sphere {
+            radius: 1.0,
+            offset: Vec3::zero(),
+        };
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::splat(2.0),
+            Quat::identity(),
+            vec3(10.0, 0.0, 0.0),
+        );
+        let hit = pick_entity(
+            &ray(vec3(10.0, 0.0, 5.0), -Vec3::unit_z()),
+            &pickable,
+            &transform,
+            None,
+        )
+        .unwrap();
+        assert_near(hit.point, vec3(10.0, 0.0, 2.0));
+        assert_near(hit.normal, Vec3::unit_z());
+        assert!(pick_entity(
+            &ray(vec3(0.0, 0.0, 5.0), -Vec3::unit_z()),
+            &pickable,
+            &transform,
+            None
+        )
+        .is_none());
+    }
+}
@@ -1,8 +1,24 @@
 use super::{
     camera::Camera,
+    camera_bindings::CameraBindings,
+    camera_bookmarks::{CameraBookmarksConfig, CameraBookmarksPlugin},
+    camera_collision::camera_collision_system,
     camera_config::CameraConfig,
+    camera_culling::{frustum_culling_system, CameraCullingStats, MeshBounds, CULLING_STAGE},
+    camera_focus::{CameraFocusConfig, CameraFocusPlugin},
+    camera_follow::{camera_follow_system, CameraFollow},
     camera_info::{CameraInfoConfig, CameraInfoPlugin},
+    camera_lens::{projection_toggle_system, CameraLens, ProjectionMode},
+    camera_motion::{delta_millis, CameraMotion},
+    camera_orbit::{CameraOrbit, CameraOrbitOpts},
+    camera_path::{CameraPathConfig, CameraPathPlugin},
+    camera_picking::{CameraPickingConfig, CameraPickingPlugin},
     camera_position::{CameraMovement, CameraPosition},
+    camera_scene::{register_camera_components, restore_scene_camera_system},
+    camera_switch::{
+        camera_cycle_system, sync_active_camera_system, ActiveCamera, CameraName,
+        CameraSwitchConfig,
+    },
     camera_view::{CameraView, CameraViewOpts},
 };
 use bevy::{
@@ -11,12 +27,33 @@ use bevy::{
         mouse::{MouseButtonInput, MouseMotion, MouseWheel},
     },
     prelude::*,
-    render::camera::PerspectiveProjection,
+    render::camera::{Camera as BevyCamera, CameraProjection, PerspectiveProjection},
+};
+
+#[cfg(feature = "gamepad")]
+use super::camera_gamepad::{
+    gamepad_camera_system, gamepad_input_system, CameraGamepadInput, CameraGamepads,
 };
 
 #[derive(Default)]
 struct MouseState {
-    left_button_pressed: bool,
+    look_button_pressed: bool,
+    pan_button_pressed: bool,
+    /// Cursor is grabbed and hidden, so mouse motion looks around without holding a button
+    cursor_grabbed: bool,
+}
+
+impl MouseState {
+    fn looking(&self) -> bool {
+        self.look_button_pressed || self.cursor_grabbed
+    }
+}
+
+/// Set `captured` while something else handles mouse drags, i.e. an editor dragging a gizmo,
+/// so that the camera doesn't look around or pan at the same time
+#[derive(Default)]
+pub struct CameraMouseCapture {
+    pub captured: bool,
 }
 
 #[derive(Default)]
@@ -29,44 +66,171 @@ struct MouseEvents {
 fn keyboard_motion_system(
     time: Res<Time>,
     keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<(&CameraView, &mut CameraPosition, &CameraConfig)>,
+    bindings: Res<CameraBindings>,
+    active_camera: Res<ActiveCamera>,
+    mut query: Query<(
+        Entity,
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraMotion,
+        &CameraConfig,
+        Option<&mut CameraOrbit>,
+        Option<&CameraFollow>,
+    )>,
 ) {
-    let dt = time.delta.as_millis();
-    for (camera_view, mut position, config) in &mut query.iter() {
-        if keyboard_input.pressed(KeyCode::W) {
-            camera_view.process_keyboard(CameraMovement::Forward, &mut position, &config, dt);
+    let dt = delta_millis(&time);
+    for (entity, mut camera_view, mut position, mut motion, config, orbit, follow) in
+        &mut query.iter()
+    {
+        // Following cameras are moved by camera_follow_system only
+        if !active_camera.is_active(entity) || follow.is_some() {
+            continue;
         }
-        if keyboard_input.pressed(KeyCode::S) {
-            camera_view.process_keyboard(CameraMovement::Backward, &mut position, &config, dt);
+        if let Some(mut orbit) = orbit {
+            if orbit_keyboard(
+                &keyboard_input,
+                &bindings,
+                &mut orbit,
+                &camera_view,
+                &config,
+                dt,
+            ) {
+                orbit.apply(&mut camera_view, &mut position);
+            }
+            continue;
         }
-        if keyboard_input.pressed(KeyCode::A) {
-            camera_view.process_keyboard(CameraMovement::Left, &mut position, &config, dt);
+        let speed = bindings.speed(&keyboard_input);
+        let movements = [
+            (bindings.forward, CameraMovement::Forward),
+            (bindings.backward, CameraMovement::Backward),
+            (bindings.left, CameraMovement::Left),
+            (bindings.right, CameraMovement::Right),
+            (bindings.up, CameraMovement::Up),
+            (bindings.down, CameraMovement::Down),
+        ];
+        if config.six_dof {
+            let mut roll = 0.0;
+            if keyboard_input.pressed(bindings.roll_left) {
+                roll -= config.roll_speed * dt;
+            }
+            if keyboard_input.pressed(bindings.roll_right) {
+                roll += config.roll_speed * dt;
+            }
+            if roll != 0.0 {
+                if config.smoothing.is_some() {
+                    motion.target.process_roll(roll);
+                } else {
+                    camera_view.process_roll(roll);
+                }
+            }
         }
-        if keyboard_input.pressed(KeyCode::D) {
-            camera_view.process_keyboard(CameraMovement::Right, &mut position, &config, dt);
+        if config.smoothing.is_some() {
+            // Smoothed cameras are moved by camera_motion_system
+            let mut wish_velocity = Vec3::zero();
+            for (key, movement) in movements.iter() {
+                if keyboard_input.pressed(*key) {
+                    wish_velocity += camera_view.movement_direction(*movement, &config);
+                }
+            }
+            motion.wish_velocity = wish_velocity * config.speed(speed);
+            continue;
+        }
+        for (key, movement) in movements.iter() {
+            if keyboard_input.pressed(*key) {
+                camera_view.process_keyboard(*movement, &mut position, &config, speed, dt);
+            }
         }
     }
 }
 
+fn orbit_keyboard(
+    keyboard_input: &Input<KeyCode>,
+    bindings: &CameraBindings,
+    orbit: &mut CameraOrbit,
+    camera_view: &CameraView,
+    config: &CameraConfig,
+    dt: f32,
+) -> bool {
+    // Scale by distance so that dollying and panning feel the same close up and far away
+    let speed = config.speed(bindings.speed(keyboard_input));
+    let velocity = speed * dt * orbit.distance * 0.2;
+    let mut moved = false;
+    if keyboard_input.pressed(bindings.forward) {
+        orbit.dolly(velocity);
+        moved = true;
+    }
+    if keyboard_input.pressed(bindings.backward) {
+        orbit.dolly(-velocity);
+        moved = true;
+    }
+    if keyboard_input.pressed(bindings.left) {
+        orbit.pan(-velocity, 0.0, camera_view);
+        moved = true;
+    }
+    if keyboard_input.pressed(bindings.right) {
+        orbit.pan(velocity, 0.0, camera_view);
+        moved = true;
+    }
+    if keyboard_input.pressed(bindings.up) {
+        orbit.pan(0.0, velocity, camera_view);
+        moved = true;
+    }
+    if keyboard_input.pressed(bindings.down) {
+        orbit.pan(0.0, -velocity, camera_view);
+        moved = true;
+    }
+    moved
+}
+
 fn mouse_button_system(
     mut mouse: ResMut<MouseEvents>,
     mouse_button_input_events: Res<Events<MouseButtonInput>>,
+    bindings: Res<CameraBindings>,
     mut mouse_state: ResMut<MouseState>,
 ) {
     for event in mouse.button_events.iter(&mouse_button_input_events) {
-        match event {
-            MouseButtonInput {
-                button: MouseButton::Left,
-                state: ElementState::Pressed,
-                ..
-            } => mouse_state.left_button_pressed = true,
-            MouseButtonInput {
-                button: MouseButton::Left,
-                state: ElementState::Released,
-                ..
-            } => mouse_state.left_button_pressed = false,
-            _ => {}
+        let pressed = match event.state {
+            ElementState::Pressed => true,
+            ElementState::Released => false,
         };
+        // Both may be bound to the same button
+        if event.button == bindings.look {
+            mouse_state.look_button_pressed = pressed;
+        }
+        if event.button == bindings.pan {
+            mouse_state.pan_button_pressed = pressed;
+        }
+    }
+}
+
+fn cursor_grab_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    bindings: Res<CameraBindings>,
+    active_camera: Res<ActiveCamera>,
+    mut windows: ResMut<Windows>,
+    mut mouse_state: ResMut<MouseState>,
+    mut camera_query: Query<(Entity, &CameraConfig)>,
+) {
+    let mut grab_cursor = false;
+    for (entity, config) in &mut camera_query.iter() {
+        if active_camera.is_active(entity) {
+            grab_cursor = config.grab_cursor;
+        }
+    }
+    let grabbed = if mouse_state.cursor_grabbed {
+        // Also release the cursor when switching to a camera that doesn't grab it
+        grab_cursor && !keyboard_input.just_pressed(bindings.release_cursor)
+    } else {
+        grab_cursor && mouse_button_input.just_pressed(bindings.look)
+    };
+    if grabbed == mouse_state.cursor_grabbed {
+        return;
+    }
+    mouse_state.cursor_grabbed = grabbed;
+    if let Some(window) = windows.get_primary_mut() {
+        window.set_cursor_lock_mode(grabbed);
+        window.set_cursor_visibility(!grabbed);
     }
 }
 
@@ -74,17 +238,66 @@ fn mouse_motion_system(
     mut mouse: ResMut<MouseEvents>,
     mouse_motion_events: Res<Events<MouseMotion>>,
     mouse_state: Res<MouseState>,
-    mut camera_query: Query<(&mut CameraView, &CameraConfig)>,
+    mouse_capture: Res<CameraMouseCapture>,
+    active_camera: Res<ActiveCamera>,
+    mut camera_query: Query<(
+        Entity,
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraMotion,
+        &CameraConfig,
+        Option<&mut CameraOrbit>,
+        Option<&CameraFollow>,
+    )>,
 ) {
-    for (mut camera_view, config) in &mut camera_query.iter() {
-        // Only consider mouse motion events when the left mouse button is pressed
-        if !mouse_state.left_button_pressed {
+    if mouse_capture.captured {
+        return;
+    }
+    for (entity, mut camera_view, mut position, mut motion, config, orbit, follow) in
+        &mut camera_query.iter()
+    {
+        if !active_camera.is_active(entity) || follow.is_some() {
+            continue;
+        }
+        if let Some(mut orbit) = orbit {
+            // Pan drag pans the target, look drag orbits around it. Panning takes priority
+            // since looking is always on while the cursor is grabbed.
+            if !mouse_state.looking() && !mouse_state.pan_button_pressed {
+                return;
+            }
+            let mut moved = false;
+            for event in mouse.motion_events.iter(&mouse_motion_events) {
+                let dx = event.delta.x() * config.mouse_sensitivity;
+                let dy = event.delta.y() * config.mouse_sensitivity;
+                if mouse_state.pan_button_pressed {
+                    let pan_speed = orbit.distance * 0.01;
+                    orbit.pan(-dx * pan_speed, dy * pan_speed, &camera_view);
+                } else {
+                    orbit.rotate(dx, if config.invert_y { -dy } else { dy });
+                }
+                moved = true;
+            }
+            if moved {
+                orbit.apply(&mut camera_view, &mut position);
+            }
+            continue;
+        }
+
+        // Only consider mouse motion events when the look mouse button is pressed or the
+        // cursor is grabbed
+        if !mouse_state.looking() {
             return;
         }
 
         for event in mouse.motion_events.iter(&mouse_motion_events) {
             let delta: Vec2 = event.delta;
-            camera_view.process_mouse_move(delta.x(), delta.y(), &config);
+            if config.smoothing.is_some() {
+                motion
+                    .target
+                    .process_mouse_move(delta.x(), delta.y(), &config);
+            } else {
+                camera_view.process_mouse_move(delta.x(), delta.y(), &config);
+            }
         }
     }
 }
@@ -92,17 +305,75 @@ fn mouse_motion_system(
 fn mouse_wheel_system(
     mut mouse: ResMut<MouseEvents>,
     mouse_wheel_events: Res<Events<MouseWheel>>,
-    mut camera_query: Query<(&mut CameraView, &mut PerspectiveProjection)>,
+    active_camera: Res<ActiveCamera>,
+    mut camera_query: Query<(
+        Entity,
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraMotion,
+        &mut CameraLens,
+        &CameraConfig,
+        Option<&mut CameraOrbit>,
+        Option<&CameraFollow>,
+    )>,
 ) {
-    for (mut camera_view, mut projection) in &mut camera_query.iter() {
+    for (entity, mut camera_view, mut position, mut motion, mut lens, config, orbit, follow) in
+        &mut camera_query.iter()
+    {
+        if !active_camera.is_active(entity) || follow.is_some() {
+            continue;
+        }
+        // Moving an orthographic camera doesn't change the size of things, so always scale
+        if lens.is_orthographic() {
+            for event in mouse.wheel_events.iter(&mouse_wheel_events) {
+                lens.process_scale(event.y / 10.0);
+            }
+            continue;
+        }
+        if let Some(mut orbit) = orbit {
+            // In orbit mode the wheel dollies towards/away from the target instead of zooming
+            let mut moved = false;
+            for event in mouse.wheel_events.iter(&mouse_wheel_events) {
+                let delta = event.y * orbit.distance * 0.1;
+                orbit.dolly(delta);
+                moved = true;
+            }
+            if moved {
+                orbit.apply(&mut camera_view, &mut position);
+            }
+            continue;
+        }
         for event in mouse.wheel_events.iter(&mouse_wheel_events) {
             let dy: f32 = event.y / 10.0;
-            camera_view.process_mouse_wheel(dy);
-            // TODO: not sure why this change doesn't affect the actual projection.
-            // If we remove the projection from the Camera Entity then things don't
-            // render properly, so projection is definitely taken into account, but
-            // possibly only the very first time.
-            projection.fov = camera_view.zoom.to_radians();
+            if config.smoothing.is_some() {
+                motion.target.process_mouse_wheel(dy, &config);
+            } else {
+                camera_view.process_mouse_wheel(dy, &config);
+            }
+        }
+    }
+}
+
+fn camera_motion_system(
+    time: Res<Time>,
+    mut camera_query: Query<(
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraMotion,
+        &CameraConfig,
+        Option<&CameraOrbit>,
+    )>,
+) {
+    let dt = delta_millis(&time);
+    for (mut camera_view, mut position, mut motion, config, orbit) in &mut camera_query.iter() {
+        // Orbit cameras are driven directly by their CameraOrbit
+        let smoothing = match (config.smoothing, orbit) {
+            (Some(smoothing), None) => smoothing,
+            _ => continue,
+        };
+        if let Some((view, pos)) = motion.step(&camera_view, position.pos(), &smoothing, dt) {
+            *camera_view = view;
+            *position = pos.into();
         }
     }
 }
@@ -113,10 +384,22 @@ fn mouse_wheel_system(
 // of the two camera components that require an update when they change.
 //
 fn on_camera_view_changed(
-    mut camera_query: Query<(Changed<CameraView>, &CameraPosition, &mut Transform)>,
+    mut camera_query: Query<(
+        Changed<CameraView>,
+        &CameraPosition,
+        &mut Transform,
+        &mut PerspectiveProjection,
+    )>,
 ) {
-    for (camera_view, position, mut transform) in &mut camera_query.iter() {
+    for (camera_view, position, mut transform, mut projection) in &mut camera_query.iter() {
         update_camera(&camera_view, position, &mut transform);
+
+        // Only touch the projection when the zoom actually changed, otherwise we'd flag it as
+        // changed and recompute the projection matrix on every view change.
+        let fov = camera_view.zoom.to_radians();
+        if projection.fov != fov {
+            projection.fov = fov;
+        }
     }
 }
 
@@ -128,6 +411,32 @@ fn on_camera_position_changed(
     }
 }
 
+// Bevy's camera system only computes the projection matrix when the camera is added or
+// the window is resized, so changes to the projection, i.e. fov, would never be rendered.
+// Cameras without a lens, i.e. ones not added via CameraTrait, are always perspective.
+fn on_projection_changed(
+    mut camera_query: Query<(
+        Changed<PerspectiveProjection>,
+        Option<&CameraLens>,
+        &mut BevyCamera,
+    )>,
+) {
+    for (projection, lens, mut camera) in &mut camera_query.iter() {
+        camera.projection_matrix = match lens {
+            Some(lens) => lens.projection_matrix(&projection),
+            None => projection.get_projection_matrix(),
+        };
+    }
+}
+
+fn on_lens_changed(
+    mut camera_query: Query<(&PerspectiveProjection, Changed<CameraLens>, &mut BevyCamera)>,
+) {
+    for (projection, lens, mut camera) in &mut camera_query.iter() {
+        camera.projection_matrix = lens.projection_matrix(projection);
+    }
+}
+
 fn update_camera(
     camera_view: &CameraView,
     position: &CameraPosition,
@@ -139,6 +448,12 @@ fn update_camera(
 #[derive(Default)]
 struct CameraPlugin {
     camera_info: Option<CameraInfoConfig>,
+    bookmarks: Option<CameraBookmarksConfig>,
+    path: Option<CameraPathConfig>,
+    picking: Option<CameraPickingConfig>,
+    focus: Option<CameraFocusConfig>,
+    switch: CameraSwitchConfig,
+    bindings: CameraBindings,
 }
 
 impl Plugin for CameraPlugin {
@@ -151,14 +466,53 @@ impl Plugin for CameraPlugin {
             }
             None => {}
         }
+        if let Some(bookmarks) = self.bookmarks {
+            app.add_plugin(CameraBookmarksPlugin { config: bookmarks });
+        }
+        if let Some(path) = self.path {
+            app.add_plugin(CameraPathPlugin { config: path });
+        }
+        if let Some(picking) = self.picking {
+            app.add_plugin(CameraPickingPlugin { config: picking });
+        }
+        if let Some(focus) = self.focus {
+            app.add_plugin(CameraFocusPlugin { config: focus });
+        }
+        register_camera_components(app);
         app.init_resource::<MouseEvents>()
             .init_resource::<MouseState>()
-            .add_system(keyboard_motion_system.system())
-            .add_system(mouse_button_system.system())
+            .init_resource::<CameraMouseCapture>()
+            .init_resource::<ActiveCamera>()
+            .init_resource::<CameraInstalls>()
+            .init_resource::<MeshBounds>()
+            .init_resource::<CameraCullingStats>()
+            .add_resource(self.bindings)
+            .add_resource(self.switch)
+            .add_startup_system(install_cameras.system())
+            .add_system(camera_cycle_system.system())
+            .add_system(sync_active_camera_system.system())
+            .add_system(restore_scene_camera_system.system())
+            .add_system(keyboard_motion_system.system());
+        // Gamepad motion adds to the wish velocity keyboard_motion_system resets each frame
+        #[cfg(feature = "gamepad")]
+        app.init_resource::<CameraGamepadInput>()
+            .init_resource::<CameraGamepads>()
+            .add_system(gamepad_input_system.system())
+            .add_system(gamepad_camera_system.system());
+        app.add_system(mouse_button_system.system())
+            .add_system(cursor_grab_system.system())
             .add_system(mouse_motion_system.system())
             .add_system(mouse_wheel_system.system())
+            .add_system(projection_toggle_system.system())
+            .add_system(camera_motion_system.system())
+            .add_system(camera_follow_system.system())
+            .add_system(camera_collision_system.system())
             .add_system(on_camera_view_changed.system())
-            .add_system(on_camera_position_changed.system());
+            .add_system(on_camera_position_changed.system())
+            .add_system_to_stage(stage::POST_UPDATE, on_projection_changed.system())
+            .add_system_to_stage(stage::POST_UPDATE, on_lens_changed.system())
+            .add_stage_after(stage::POST_UPDATE, CULLING_STAGE)
+            .add_system_to_stage(CULLING_STAGE, frustum_culling_system.system());
     }
 }
 
@@ -166,28 +520,159 @@ impl Plugin for CameraPlugin {
 // Add Camera Trait
 //
 
+pub enum CameraMode {
+    /// Free flying camera positioned via `AddCameraOpts::position` and `AddCameraOpts::view`
+    Fly,
+    /// Camera orbiting a target, `AddCameraOpts::position` and `AddCameraOpts::view` are ignored
+    Orbit(CameraOrbitOpts),
+}
+
+impl Default for CameraMode {
+    fn default() -> Self {
+        CameraMode::Fly
+    }
+}
+
+/// Options for a camera added via `CameraTrait::add_camera_from`.
+/// When adding multiple cameras, `bindings`, `switch`, `info`, `bookmarks`, `path`, `picking`
+/// and `focus` are shared by all cameras and taken from the first camera that's added,
+/// setting them on any other camera panics.
+/// Only the active camera is rendered, bevy can't render a camera into a viewport or texture
+/// yet, so there is no picture-in-picture of a second camera.
 pub struct AddCameraOpts {
+    /// Identifies the camera in order to switch to it via `ActiveCamera`, needs to be unique
+    pub name: String,
     pub position: Vec3,
     pub view: CameraViewOpts,
+    pub mode: CameraMode,
+    /// Projection the camera starts out with, `CameraBindings::toggle_projection` switches it
+    pub projection: ProjectionMode,
+    pub config: CameraConfig,
+    pub bindings: CameraBindings,
+    pub switch: CameraSwitchConfig,
     pub info: Option<CameraInfoConfig>,
+    pub bookmarks: Option<CameraBookmarksConfig>,
+    pub path: Option<CameraPathConfig>,
+    pub picking: Option<CameraPickingConfig>,
+    pub focus: Option<CameraFocusConfig>,
 }
 
 impl Default for AddCameraOpts {
     fn default() -> Self {
         AddCameraOpts {
+            name: "main".to_string(),
             position: CameraPosition::default().into(),
             view: Default::default(),
+            mode: Default::default(),
+            projection: Default::default(),
+            config: Default::default(),
+            bindings: Default::default(),
+            switch: Default::default(),
             info: None,
+            bookmarks: None,
+            path: None,
+            picking: None,
+            focus: None,
+        }
+    }
+}
+
+/// Cameras added via `CameraTrait::add_camera_from` waiting to be spawned at startup
+#[derive(Default)]
+struct CameraInstalls {
+    pending: Vec<AddCameraOpts>,
+}
+
+fn install_cameras(
+    mut commands: Commands,
+    mut installs: ResMut<CameraInstalls>,
+    mut active_camera: ResMut<ActiveCamera>,
+) {
+    for opts in installs.pending.drain(..) {
+        // The first camera that was added is active unless one was requested explicitly
+        if active_camera.requested.is_none() {
+            active_camera.request(&opts.name);
+        }
+        install_camera(&mut commands, opts);
+    }
+}
+
+fn install_camera(commands: &mut Commands, opts: AddCameraOpts) {
+    let name = CameraName(opts.name.clone());
+    match &opts.mode {
+        CameraMode::Fly => {
+            let view = CameraView::new(&opts.view);
+            commands
+                .spawn(Camera {
+                    position: opts.position.into(),
+                    view,
+                    config: opts.config,
+                    motion: CameraMotion::new(&view),
+                    lens: CameraLens::new(opts.projection, view.zoom, opts.config.focus_distance),
+                    ..Default::default()
+                })
+                .with(name);
+        }
+        CameraMode::Orbit(orbit_opts) => {
+            let orbit = CameraOrbit::new(orbit_opts);
+            let mut view = CameraView::new(&opts.view);
+            let mut position = CameraPosition::default();
+            orbit.apply(&mut view, &mut position);
+            commands
+                .spawn(Camera {
+                    position,
+                    view,
+                    config: opts.config,
+                    motion: CameraMotion::new(&view),
+                    lens: CameraLens::new(opts.projection, view.zoom, orbit.distance),
+                    ..Default::default()
+                })
+                .with(name)
+                .with(orbit);
         }
     }
 }
 
-fn install_camera(mut commands: Commands, opts: Res<AddCameraOpts>) {
-    commands.spawn(Camera {
-        position: opts.position.into(),
-        view: CameraView::new(&opts.view),
-        ..Default::default()
-    });
+/// Panics if `opts` conflicts with the cameras that were added before, since the conflicting
+/// options would otherwise be ignored silently
+fn check_additional_camera(resources: &Resources, opts: &AddCameraOpts) {
+    let installs = resources.get::<CameraInstalls>().unwrap();
+    if installs
+        .pending
+        .iter()
+        .any(|pending| pending.name == opts.name)
+    {
+        panic!(
+            "A camera named '{}' was added already, each camera needs a unique name",
+            opts.name
+        );
+    }
+    // Repeating the bindings of the first camera, or leaving them at their defaults, is fine
+    let bindings = *resources.get::<CameraBindings>().unwrap();
+    let switch = *resources.get::<CameraSwitchConfig>().unwrap();
+    let shared = [
+        ("info", opts.info.is_some()),
+        ("bookmarks", opts.bookmarks.is_some()),
+        ("path", opts.path.is_some()),
+        ("picking", opts.picking.is_some()),
+        ("focus", opts.focus.is_some()),
+        (
+            "bindings",
+            opts.bindings != bindings && opts.bindings != CameraBindings::default(),
+        ),
+        (
+            "switch",
+            opts.switch != switch && opts.switch != CameraSwitchConfig::default(),
+        ),
+    ];
+    for (option, set) in shared.iter() {
+        if *set {
+            panic!(
+                "AddCameraOpts::{} of camera '{}' is shared by all cameras, set it on the first camera that's added",
+                option, opts.name
+            );
+        }
+    }
 }
 
 pub trait CameraTrait {
@@ -197,11 +682,25 @@ pub trait CameraTrait {
 
 impl CameraTrait for AppBuilder {
     fn add_camera_from(&mut self, opts: AddCameraOpts) -> &mut Self {
-        self.add_plugin(CameraPlugin {
-            camera_info: opts.info,
-        })
-        .add_resource(opts)
-        .add_startup_system(install_camera.system());
+        // Only the first camera installs the plugin, otherwise all systems would run once per camera
+        if self.resources().get::<CameraInstalls>().is_some() {
+            check_additional_camera(self.resources(), &opts);
+        } else {
+            self.add_plugin(CameraPlugin {
+                camera_info: opts.info,
+                bookmarks: opts.bookmarks,
+                path: opts.path,
+                picking: opts.picking,
+                focus: opts.focus,
+                switch: opts.switch,
+                bindings: opts.bindings,
+            });
+        }
+        self.resources_mut()
+            .get_mut::<CameraInstalls>()
+            .expect("CameraPlugin should have been added")
+            .pending
+            .push(opts);
         self
     }
     fn add_camera(&mut self) -> &mut Self {
@@ -1,6 +1,7 @@
 use bevy::{math::vec3, prelude::*};
 
-#[derive(Debug)]
+/// Registered with the type registry so that it is persisted along with scenes
+#[derive(Debug, Properties)]
 pub struct CameraPosition {
     pos: Vec3,
 }
@@ -10,20 +11,19 @@ impl CameraPosition {
         self.pos += delta;
     }
 
-    pub fn dec_pos(&mut self, delta: Vec3) {
-        self.pos -= delta;
-    }
-
     pub fn pos(&self) -> Vec3 {
         self.pos
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CameraMovement {
     Forward,
     Backward,
     Left,
     Right,
+    Up,
+    Down,
 }
 
 impl From<Vec3> for CameraPosition {
@@ -0,0 +1,181 @@
+use super::{
+    camera_config::CameraConfig, camera_motion::CameraMotion, camera_orbit::CameraOrbit,
+    camera_position::CameraPosition, camera_switch::ActiveCamera, camera_view::CameraView,
+};
+use bevy::prelude::*;
+
+//
+// Camera persistence via scenes.
+//
+// CameraView, CameraPosition and CameraConfig are registered with the type registry, so
+// Scene::from_world includes them when saving a scene. Loading that scene spawns a new
+// entity with just the registered components though, which isn't a working camera.
+// Instead of keeping that entity around, the loaded state is moved into the active camera.
+//
+
+pub(crate) fn register_camera_components(app: &mut AppBuilder) {
+    app.register_component::<CameraView>()
+        .register_component::<CameraPosition>()
+        .register_component::<CameraConfig>();
+}
+
+/// Settings loaded from a scene, with the ones that aren't persisted taken from `current`
+pub fn restore_config(current: &CameraConfig, persisted: &CameraConfig) -> CameraConfig {
+    CameraConfig {
+        vertical_axis: current.vertical_axis,
+        smoothing: current.smoothing,
+        bounds: current.bounds,
+        ground: current.ground,
+        collision_radius: current.collision_radius,
+        #[cfg(feature = "gamepad")]
+        gamepad: current.gamepad,
+        ..*persisted
+    }
+}
+
+pub(crate) fn restore_scene_camera_system(
+    mut commands: Commands,
+    mut active_camera: ResMut<ActiveCamera>,
+    mut loaded_query: Query<(
+        Entity,
+        &CameraView,
+        &CameraPosition,
+        &CameraConfig,
+        Option<&CameraMotion>,
+    )>,
+    mut camera_query: Query<(
+        Entity,
+        &mut CameraView,
+        &mut CameraPosition,
+        &mut CameraConfig,
+        &mut CameraMotion,
+        Option<&mut CameraOrbit>,
+    )>,
+) {
+    // Keep the loaded camera around until there is a camera to move its state into
+    if active_camera.entity().is_none() {
+        return;
+    }
+    // Cameras added via CameraTrait always have a CameraMotion, entities loaded from a
+    // scene never do since it isn't registered
+    let mut loaded = None;
+    for (entity, view, position, config, motion) in &mut loaded_query.iter() {
+        if motion.is_some() {
+            continue;
+        }
+        loaded = Some((*view, position.pos(), *config));
+        commands.despawn(entity);
+    }
+    let (loaded_view, loaded_position, loaded_config) = match loaded {
+        Some(loaded) => loaded,
+        None => return,
+    };
+
+    for (entity, mut view, mut position, mut config, mut motion, orbit) in &mut camera_query.iter()
+    {
+        if !active_camera.is_active(entity) {
+            continue;
+        }
+        *view = loaded_view;
+        view.update_camera_vectors();
+        *position = loaded_position.into();
+        *config = restore_config(&config, &loaded_config);
+        *motion = CameraMotion::new(&view);
+        if let Some(mut orbit) = orbit {
+            orbit.align_to(&view, &position);
+        }
+    }
+    // The loaded scene may contain a bevy camera which took over the 3D pass when spawned
+    active_camera.resync();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libs::camera::{camera_motion::CameraSmoothing, camera_view::CameraViewOpts};
+    use bevy::{
+        asset::AssetLoader,
+        core::CorePlugin,
+        ecs::FromResources,
+        math::vec3,
+        property::{DynamicProperties, Property},
+        scene::SceneLoader,
+        type_registry::{TypeRegistry, TypeRegistryPlugin},
+    };
+    use std::{any::type_name, path::Path};
+
+    fn component<'a, T>(components: &'a [DynamicProperties]) -> &'a DynamicProperties {
+        components
+            .iter()
+            .find(|component| component.type_name == type_name::<T>())
+            .unwrap_or_else(|| panic!("{} wasn't persisted", type_name::<T>()))
+    }
+
+    #[test]
+    fn camera_round_trips_through_scene_ron() {
+        let mut app = App::build();
+        app.add_plugin(TypeRegistryPlugin::default())
+            .add_plugin(CorePlugin::default());
+        register_camera_components(&mut app);
+        let resources = app.resources();
+        let type_registry = resources.get::<TypeRegistry>().unwrap();
+
+        let mut view = CameraView::new(&CameraViewOpts {
+            yaw: -120.0,
+            pitch: 15.0,
+            roll: 5.0,
+        });
+        view.zoom = 30.0;
+        let position = vec3(1.0, 2.0, 3.0);
+        let config = CameraConfig {
+            mov_speed: 0.02,
+            invert_y: true,
+            grab_cursor: true,
+            min_zoom: 5.0,
+            frustum_culling: true,
+            ..Default::default()
+        };
+        let mut world = World::new();
+        world.spawn((view, CameraPosition::from(position), config));
+
+        let scene = Scene::from_world(&world, &type_registry.component.read());
+        let ron = scene.serialize_ron(&type_registry.property.read()).unwrap();
+        let loaded = SceneLoader::from_resources(resources)
+            .from_bytes(Path::new("camera.scn"), ron.into_bytes())
+            .unwrap();
+        assert_eq!(loaded.entities.len(), 1);
+        let components = &loaded.entities[0].components;
+
+        let mut loaded_view = CameraView::default();
+        loaded_view.apply(component::<CameraView>(components));
+        assert_eq!(loaded_view.yaw, view.yaw);
+        assert_eq!(loaded_view.pitch, view.pitch);
+        assert_eq!(loaded_view.roll, view.roll);
+        assert_eq!(loaded_view.zoom, 30.0);
+        assert_eq!(loaded_view.front, view.front);
+        assert_eq!(loaded_view.orientation, view.orientation);
+
+        let mut loaded_position = CameraPosition::default();
+        loaded_position.apply(component::<CameraPosition>(components));
+        assert_eq!(loaded_position.pos(), position);
+
+        let mut loaded_config = CameraConfig::default();
+        loaded_config.apply(component::<CameraConfig>(components));
+        assert_eq!(loaded_config.mov_speed, 0.02);
+        assert!(loaded_config.invert_y);
+        assert!(loaded_config.grab_cursor);
+        assert_eq!(loaded_config.min_zoom, 5.0);
+        assert!(loaded_config.frustum_culling);
+
+        // Settings the property system can't persist are kept
+        let current = CameraConfig {
+            smoothing: Some(CameraSmoothing::default()),
+            collision_radius: Some(0.5),
+            ..Default::default()
+        };
+        let restored = restore_config(&current, &loaded_config);
+        assert_eq!(restored.mov_speed, 0.02);
+        assert!(restored.smoothing.is_some());
+        assert_eq!(restored.collision_radius, Some(0.5));
+    }
+}
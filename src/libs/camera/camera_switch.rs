@@ -0,0 +1,296 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::{ActiveCameras, Camera as BevyCamera},
+        render_graph::base,
+    },
+};
+
+/// Name of a camera added via `CameraTrait::add_camera_from`, used to switch to it
+#[derive(Clone, Debug, PartialEq)]
+pub struct CameraName(pub String);
+
+/// The camera that renders to the window and responds to input.
+/// Set `requested` to the name of a camera in order to switch to it.
+#[derive(Default, Debug)]
+pub struct ActiveCamera {
+    pub requested: Option<String>,
+    entity: Option<Entity>,
+    synced: Option<Entity>,
+}
+
+impl ActiveCamera {
+    pub fn entity(&self) -> Option<Entity> {
+        self.entity
+    }
+
+    pub fn is_active(&self, entity: Entity) -> bool {
+        self.entity == Some(entity)
+    }
+
+    pub fn request(&mut self, name: &str) {
+        self.requested = Some(name.to_string());
+    }
+
+    /// Active camera that doesn't need to be resolved by `sync_active_camera_system`
+    #[cfg(test)]
+    pub(crate) fn with_entity(entity: Entity) -> Self {
+        ActiveCamera {
+            requested: None,
+            entity: Some(entity),
+            synced: Some(entity),
+        }
+    }
+
+    /// Makes the requested camera the one bevy renders with again, even if it didn't change
+    pub(crate) fn resync(&mut self) {
+        self.synced = None;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraSwitchConfig {
+    /// Switches to the next camera
+    pub cycle: KeyCode,
+}
+
+impl Default for CameraSwitchConfig {
+    fn default() -> Self {
+        CameraSwitchConfig {
+            cycle: KeyCode::Tab,
+        }
+    }
+}
+
+pub(crate) fn camera_cycle_system(
+    config: Res<CameraSwitchConfig>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut active_camera: ResMut<ActiveCamera>,
+    mut camera_query: Query<&CameraName>,
+) {
+    if !keyboard_input.just_pressed(config.cycle) {
+        return;
+    }
+    // Cycle in name order since query order isn't guaranteed to be stable
+    let mut names: Vec<String> = Vec::new();
+    for name in &mut camera_query.iter() {
+        names.push(name.0.clone());
+    }
+    if names.len() < 2 {
+        return;
+    }
+    names.sort();
+    let current = active_camera
+        .requested
+        .clone()
+        .and_then(|name| names.iter().position(|n| *n == name))
+        .unwrap_or(0);
+    let next = names[(current + 1) % names.len()].clone();
+    active_camera.requested = Some(next);
+}
+
+/// Resolves the requested camera and makes it the one bevy renders with, all other cameras
+/// are detached from the 3D pass.
+pub(crate) fn sync_active_camera_system(
+    mut active_camera: ResMut<ActiveCamera>,
+    mut active_cameras: ResMut<ActiveCameras>,
+    mut camera_query: Query<(Entity, &CameraName, &mut BevyCamera)>,
+) {
+    let mut entity = None;
+    for (camera_entity, name, _) in &mut camera_query.iter() {
+        let requested = match &active_camera.requested {
+            Some(requested) => *requested == name.0,
+            None => true,
+        };
+        if requested {
+            entity = Some(camera_entity);
+            break;
+        }
+    }
+    let entity = match entity {
+        Some(entity) => entity,
+        None => return,
+    };
+    if active_camera.synced == Some(entity) {
+        return;
+    }
+
+    for (camera_entity, _, mut camera) in &mut camera_query.iter() {
+        camera.name = if camera_entity == entity {
+            Some(base::camera::CAMERA3D.to_string())
+        } else {
+            None
+        };
+    }
+    active_cameras.set(base::camera::CAMERA3D, entity);
+    active_camera.entity = Some(entity);
+    active_camera.synced = Some(entity);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Headless {
+        world: World,
+        resources: Resources,
+        schedule: Schedule,
+    }
+
+    impl Headless {
+        fn new(names: &[&str]) -> Self {
+            let mut world = World::new();
+            for name in names {
+                world.spawn((CameraName(name.to_string()), BevyCamera::default()));
+            }
+            let mut resources = Resources::default();
+            resources.insert(CameraSwitchConfig::default());
+            resources.insert(Input::<KeyCode>::default());
+            resources.insert(ActiveCamera::default());
+            let mut active_cameras = ActiveCameras::default();
+            active_cameras.add(base::camera::CAMERA3D);
+            resources.insert(active_cameras);
+
+            let mut schedule = Schedule::default();
+            schedule.add_stage("update");
+            schedule.add_system_to_stage("update", camera_cycle_system.system());
+            schedule.add_system_to_stage("update", sync_active_camera_system.system());
+            schedule.initialize(&mut resources);
+            let mut headless = Headless {
+                world,
+                resources,
+                schedule,
+            };
+            headless.frame();
+            headless
+        }
+
+        fn frame(&mut self) {
+            self.schedule.run(&mut self.world, &mut self.resources);
+            self.resources.get_mut::<Input<KeyCode>>().unwrap().update();
+        }
+
+        fn cycle(&mut self) {
+            self.resources
+                .get_mut::<Input<KeyCode>>()
+                .unwrap()
+                .press(KeyCode::Tab);
+            self.frame();
+            self.resources
+                .get_mut::<Input<KeyCode>>()
+                .unwrap()
+                .release(KeyCode::Tab);
+        }
+
+        fn camera(&self, name: &str) -> Option<Entity> {
+            self.world
+                .query::<(Entity, &CameraName)>()
+                .iter()
+                .find(|(_, camera_name)| camera_name.0 == name)
+                .map(|(entity, _)| entity)
+        }
+
+        /// Name of the active camera, checks that it is the only one bevy renders with
+        fn active(&self) -> String {
+            let entity = self
+                .resources
+                .get::<ActiveCamera>()
+                .unwrap()
+                .entity()
+                .expect("no active camera");
+            let active_cameras = self.resources.get::<ActiveCameras>().unwrap();
+            assert_eq!(active_cameras.get(base::camera::CAMERA3D), Some(entity));
+            for (camera_entity, camera) in self.world.query::<(Entity, &BevyCamera)>().iter() {
+                assert_eq!(camera.name.is_some(), camera_entity == entity);
+            }
+            self.world.get::<CameraName>(entity).unwrap().0.clone()
+        }
+    }
+
+    #[test]
+    fn cycles_in_name_order_and_wraps_around() {
+        let mut headless = Headless::new(&["b", "c", "a"]);
+        headless.cycle();
+        assert_eq!(headless.active(), "b");
+        headless.cycle();
+        assert_eq!(headless.active(), "c");
+        headless.cycle();
+        assert_eq!(headless.active(), "a");
+        headless.cycle();
+        assert_eq!(headless.active(), "b");
+    }
+
+    #[test]
+    fn single_camera_doesnt_cycle() {
+        let mut headless = Headless::new(&["main"]);
+        assert_eq!(headless.active(), "main");
+        headless.cycle();
+        assert_eq!(headless.active(), "main");
+        assert_eq!(
+            headless.resources.get::<ActiveCamera>().unwrap().requested,
+            None
+        );
+    }
+
+    #[test]
+    fn switches_to_requested_camera() {
+        let mut headless = Headless::new(&["a", "b", "c"]);
+        headless
+            .resources
+            .get_mut::<ActiveCamera>()
+            .unwrap()
+            .request("c");
+        headless.frame();
+        assert_eq!(headless.active(), "c");
+        // Cycling continues from the requested camera
+        headless.cycle();
+        assert_eq!(headless.active(), "a");
+
+        // Unknown names keep the current camera
+        headless
+            .resources
+            .get_mut::<ActiveCamera>()
+            .unwrap()
+            .request("missing");
+        headless.frame();
+        assert_eq!(headless.active(), "a");
+    }
+
+    #[test]
+    fn resync_takes_the_3d_pass_back() {
+        let mut headless = Headless::new(&["a", "b"]);
+        headless
+            .resources
+            .get_mut::<ActiveCamera>()
+            .unwrap()
+            .request("b");
+        headless.frame();
+        let b = headless.camera("b").unwrap();
+
+        // A camera loaded with a scene takes over the 3D pass and is despawned again
+        let loaded = headless.world.spawn((BevyCamera::default(),));
+        headless
+            .resources
+            .get_mut::<ActiveCameras>()
+            .unwrap()
+            .set(base::camera::CAMERA3D, loaded);
+        headless.world.despawn(loaded).unwrap();
+        headless.frame();
+        let active_cameras = headless.resources.get::<ActiveCameras>().unwrap();
+        assert_eq!(active_cameras.get(base::camera::CAMERA3D), Some(loaded));
+        drop(active_cameras);
+
+        headless
+            .resources
+            .get_mut::<ActiveCamera>()
+            .unwrap()
+            .resync();
+        headless.frame();
+        assert_eq!(headless.active(), "b");
+        assert!(headless
+            .resources
+            .get::<ActiveCamera>()
+            .unwrap()
+            .is_active(b));
+    }
+}
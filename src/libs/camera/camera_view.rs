@@ -1,11 +1,12 @@
 use super::{
-    camera_config::CameraConfig,
+    camera_config::{CameraConfig, CameraSpeed, VerticalAxis},
     camera_position::{CameraMovement, CameraPosition},
 };
 use bevy::{math::vec3, prelude::*};
-use std::ops::Mul;
+use std::{f32::consts::PI, ops::Mul};
 
-#[derive(Clone, Copy, Debug)]
+/// Registered with the type registry so that it is persisted along with scenes
+#[derive(Clone, Copy, Debug, Properties)]
 pub struct CameraView {
     pub front: Vec3,
     pub up: Vec3,
@@ -14,6 +15,11 @@ pub struct CameraView {
 
     pub yaw: f32,
     pub pitch: f32,
+    pub roll: f32,
+
+    /// Rotation from the default orientation (looking down -Z with Y up) to the current one.
+    /// Kept in sync with yaw, pitch and roll, which are derived from it in 6-DoF mode.
+    pub orientation: Quat,
 
     pub zoom: f32,
 }
@@ -21,6 +27,7 @@ pub struct CameraView {
 pub struct CameraViewOpts {
     pub yaw: f32,
     pub pitch: f32,
+    pub roll: f32,
 }
 
 impl Default for CameraViewOpts {
@@ -28,14 +35,20 @@ impl Default for CameraViewOpts {
         Self {
             yaw: -90.0,
             pitch: 0.0,
+            roll: 0.0,
         }
     }
 }
 
+impl Default for CameraView {
+    fn default() -> Self {
+        CameraView::new(&Default::default())
+    }
+}
+
 impl CameraView {
     pub fn new(opts: &CameraViewOpts) -> Self {
-        // Cannot just impl default on CameraView itself as we need a constructor
-        // in order to properly initialize the view after it was created.
+        // front, up, right and orientation are derived from yaw, pitch and roll below
         let mut view = CameraView {
             front: vec3(0.0, 0.0, -1.0),
             up: vec3(0.0, 0.0, 0.0),
@@ -43,6 +56,8 @@ impl CameraView {
             world_up: vec3(0.0, 1.0, 0.0),
             yaw: opts.yaw,
             pitch: opts.pitch,
+            roll: opts.roll,
+            orientation: Quat::identity(),
             zoom: 45.0,
         };
         view.update_camera_vectors();
@@ -55,10 +70,10 @@ impl CameraView {
     }
 
     pub fn get_back_view(&self, position: &CameraPosition) -> Mat4 {
+        // Turning around the camera's own up axis reverses front and right, but keeps up
         let mut reversed = self.clone();
-        reversed.yaw += 180.0_f32;
-        reversed.pitch = -reversed.pitch;
-        reversed.update_camera_vectors();
+        reversed.orientation = self.orientation * Quat::from_rotation_y(PI);
+        reversed.sync_from_orientation();
         reversed.get_view(position)
     }
 
@@ -67,24 +82,52 @@ impl CameraView {
         direction: CameraMovement,
         position: &mut CameraPosition,
         config: &CameraConfig,
-        dt: u128,
+        speed: CameraSpeed,
+        dt: f32,
     ) {
-        let velocity = config.mov_speed * dt as f32;
+        let velocity = config.speed(speed) * dt;
+        position.inc_pos(self.movement_direction(direction, config).mul(velocity));
+    }
+
+    /// Unit vector along which the given movement moves the camera
+    pub fn movement_direction(&self, direction: CameraMovement, config: &CameraConfig) -> Vec3 {
+        let up = match config.vertical_axis {
+            VerticalAxis::World => self.world_up,
+            VerticalAxis::Camera => self.up,
+        };
         match direction {
-            CameraMovement::Forward => position.inc_pos(self.front.mul(velocity)),
-            CameraMovement::Backward => position.dec_pos(self.front.mul(velocity)),
-            CameraMovement::Left => position.dec_pos(self.right.mul(velocity)),
-            CameraMovement::Right => position.inc_pos(self.right.mul(velocity)),
+            CameraMovement::Forward => self.front,
+            CameraMovement::Backward => -self.front,
+            CameraMovement::Left => -self.right,
+            CameraMovement::Right => self.right,
+            CameraMovement::Up => up,
+            CameraMovement::Down => -up,
         }
     }
 
     pub fn process_mouse_move(&mut self, dx: f32, dy: f32, config: &CameraConfig) {
         let dx = dx * config.mouse_sensitivity;
-        let dy = dy * config.mouse_sensitivity;
+        // Mouse motion points down the screen, so moving the mouse up looks up unless inverted
+        let dy = if config.invert_y { dy } else { -dy } * config.mouse_sensitivity;
 
-        self.yaw += dx;
-        // Change this to += if not on a Mac, i.e. if your Y mouse/trackpad drag work backwards
-        self.pitch -= dy;
+        self.process_look(dx, dy, config);
+    }
+
+    /// Turns the camera by `d_yaw` degrees to the right and `d_pitch` degrees up
+    pub fn process_look(&mut self, d_yaw: f32, d_pitch: f32, config: &CameraConfig) {
+        if config.six_dof {
+            // Rotate around the camera's own axes, so there's no gimbal lock and no need to
+            // constrain pitch
+            self.orientation = (self.orientation
+                * Quat::from_rotation_y(-d_yaw.to_radians())
+                * Quat::from_rotation_x(d_pitch.to_radians()))
+            .normalize();
+            self.sync_from_orientation();
+            return;
+        }
+
+        self.yaw += d_yaw;
+        self.pitch += d_pitch;
 
         if config.constrain_pitch {
             self.constrain_pitch();
@@ -92,6 +135,13 @@ impl CameraView {
         self.update_camera_vectors()
     }
 
+    /// Rolls around the view direction by `degrees`, positive values roll to the right.
+    pub fn process_roll(&mut self, degrees: f32) {
+        self.orientation =
+            (self.orientation * Quat::from_rotation_z(-degrees.to_radians())).normalize();
+        self.sync_from_orientation();
+    }
+
     fn constrain_pitch(&mut self) {
         if self.pitch > 89.0 {
             self.pitch = 89.0
@@ -101,24 +151,229 @@ impl CameraView {
         }
     }
 
-    pub fn process_mouse_wheel(&mut self, dy: f32) {
-        self.zoom -= dy;
-        if self.zoom < 1.0 {
-            self.zoom = 1.0
+    pub fn process_mouse_wheel(&mut self, dy: f32, config: &CameraConfig) {
+        self.process_zoom(dy, config);
+    }
+
+    /// Zooms in by `degrees` of field of view, negative values zoom out
+    pub fn process_zoom(&mut self, degrees: f32, config: &CameraConfig) {
+        self.zoom -= degrees;
+        if self.zoom < config.min_zoom {
+            self.zoom = config.min_zoom
         }
-        if self.zoom > 45.0 {
-            self.zoom = 45.0
+        if self.zoom > config.max_zoom {
+            self.zoom = config.max_zoom
         }
     }
 
+    /// Updates orientation and vectors from yaw, pitch and roll
     pub fn update_camera_vectors(&mut self) {
-        let front = vec3(
-            self.yaw.to_radians().cos() * self.pitch.to_radians().cos(),
-            self.pitch.to_radians().sin(),
-            self.yaw.to_radians().sin() * self.pitch.to_radians().cos(),
+        self.orientation = orientation_from_euler(self.yaw, self.pitch, self.roll);
+        self.update_vectors_from_orientation();
+    }
+
+    /// Updates vectors as well as yaw, pitch and roll from the orientation
+    pub fn sync_from_orientation(&mut self) {
+        self.update_vectors_from_orientation();
+        let (yaw, pitch, roll) = euler_from_vectors(self.front, self.up, self.world_up, self.yaw);
+        self.yaw = yaw;
+        self.pitch = pitch;
+        self.roll = roll;
+    }
+
+    fn update_vectors_from_orientation(&mut self) {
+        self.front = (self.orientation * vec3(0.0, 0.0, -1.0)).normalize();
+        self.right = (self.orientation * vec3(1.0, 0.0, 0.0)).normalize();
+        self.up = (self.orientation * vec3(0.0, 1.0, 0.0)).normalize();
+    }
+}
+
+/// Orientation for the given yaw, pitch and roll in degrees.
+/// A yaw of -90 and pitch of 0 looks down -Z, which is the identity orientation.
+pub fn orientation_from_euler(yaw: f32, pitch: f32, roll: f32) -> Quat {
+    Quat::from_rotation_y(-(yaw + 90.0).to_radians())
+        * Quat::from_rotation_x(pitch.to_radians())
+        * Quat::from_rotation_z(-roll.to_radians())
+}
+
+/// Yaw, pitch and roll in degrees of a camera with the given `front` and `up` vectors.
+/// When looking straight up or down the yaw is undefined and `fallback_yaw` is used.
+pub fn euler_from_vectors(
+    front: Vec3,
+    up: Vec3,
+    world_up: Vec3,
+    fallback_yaw: f32,
+) -> (f32, f32, f32) {
+    let pitch = front.y().max(-1.0).min(1.0).asin().to_degrees();
+
+    let horizontal = front.cross(world_up);
+    if horizontal.length_squared() < 1e-8 {
+        return (fallback_yaw, pitch, 0.0);
+    }
+    let yaw = front.z().atan2(front.x()).to_degrees();
+
+    // Up vector the camera would have without any roll
+    let unrolled_up = horizontal.normalize().cross(front).normalize();
+    let roll = unrolled_up
+        .cross(up)
+        .dot(front)
+        .atan2(unrolled_up.dot(up))
+        .to_degrees();
+    (yaw, pitch, roll)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 0.01,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    fn assert_near_vec(actual: Vec3, expected: Vec3) {
+        assert!(
+            (actual - expected).length() < 0.0001,
+            "{:?} != {:?}",
+            actual,
+            expected
         );
-        self.front = front.normalize();
-        self.right = self.front.cross(self.world_up).normalize();
-        self.up = self.right.cross(self.front).normalize();
+    }
+
+    fn six_dof() -> CameraConfig {
+        CameraConfig {
+            six_dof: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_orientation_looks_down_negative_z() {
+        let orientation = orientation_from_euler(-90.0, 0.0, 0.0);
+        assert_near_vec(orientation * -Vec3::unit_z(), -Vec3::unit_z());
+        assert_near_vec(orientation * Vec3::unit_y(), Vec3::unit_y());
+
+        let view = CameraView::default();
+        assert_near_vec(view.front, -Vec3::unit_z());
+        assert_near_vec(view.right, Vec3::unit_x());
+        assert_near_vec(view.up, Vec3::unit_y());
+    }
+
+    #[test]
+    fn yaw_and_pitch_match_the_front_vector() {
+        // yaw 0 looks down +X, yaw 90 down +Z, positive pitch looks up
+        let view = CameraView::new(&CameraViewOpts {
+            yaw: 0.0,
+            pitch: 0.0,
+            roll: 0.0,
+        });
+        assert_near_vec(view.front, Vec3::unit_x());
+        let view = CameraView::new(&CameraViewOpts {
+            yaw: 90.0,
+            pitch: 30.0,
+            roll: 0.0,
+        });
+        assert_near_vec(view.front, vec3(0.0, 0.5, 0.75f32.sqrt()));
+    }
+
+    #[test]
+    fn euler_round_trips_through_the_orientation() {
+        let angles = [
+            (-90.0, 0.0, 0.0),
+            (30.0, 20.0, 0.0),
+            (-120.0, -45.0, 15.0),
+            (170.0, 60.0, -30.0),
+            (0.0, -80.0, 90.0),
+            (-45.0, 10.0, 179.0),
+        ];
+        for (yaw, pitch, roll) in angles.iter() {
+            let orientation = orientation_from_euler(*yaw, *pitch, *roll);
+            let front = orientation * -Vec3::unit_z();
+            let up = orientation * Vec3::unit_y();
+            let (actual_yaw, actual_pitch, actual_roll) =
+                euler_from_vectors(front, up, Vec3::unit_y(), 0.0);
+            assert_near(actual_yaw, *yaw);
+            assert_near(actual_pitch, *pitch);
+            assert_near(actual_roll, *roll);
+        }
+    }
+
+    #[test]
+    fn looking_straight_up_keeps_the_fallback_yaw() {
+        let (yaw, pitch, roll) =
+            euler_from_vectors(Vec3::unit_y(), Vec3::unit_z(), Vec3::unit_y(), -37.0);
+        assert_eq!(yaw, -37.0);
+        assert_near(pitch, 90.0);
+        assert_eq!(roll, 0.0);
+
+        let mut view = CameraView::new(&CameraViewOpts {
+            yaw: -60.0,
+            pitch: 0.0,
+            roll: 0.0,
+        });
+        view.process_look(0.0, 90.0, &six_dof());
+        // asin is steep at 1, so the pitch is only roughly 90
+        assert!((view.pitch - 90.0).abs() < 0.1, "{}", view.pitch);
+        assert_near(view.yaw, -60.0);
+        assert_near_vec(view.front, Vec3::unit_y());
+    }
+
+    #[test]
+    fn six_dof_look_keeps_euler_angles_in_sync() {
+        let mut view = CameraView::default();
+        let config = six_dof();
+        view.process_look(25.0, 10.0, &config);
+        view.process_roll(20.0);
+        view.process_look(-40.0, 35.0, &config);
+        let orientation = orientation_from_euler(view.yaw, view.pitch, view.roll);
+        assert_near_vec(orientation * -Vec3::unit_z(), view.front);
+        assert_near_vec(orientation * Vec3::unit_y(), view.up);
+    }
+
+    #[test]
+    fn roll_tilts_up_to_the_right_and_keeps_front() {
+        let mut view = CameraView::default();
+        view.process_roll(30.0);
+        assert_near(view.roll, 30.0);
+        assert_near(view.yaw, -90.0);
+        assert_near(view.pitch, 0.0);
+        assert_near_vec(view.front, -Vec3::unit_z());
+        assert_near_vec(view.up, vec3(0.5, 0.75f32.sqrt(), 0.0));
+
+        view.process_roll(-30.0);
+        assert_near(view.roll, 0.0);
+        assert_near_vec(view.up, Vec3::unit_y());
+    }
+
+    #[test]
+    fn back_view_negates_front_and_keeps_up() {
+        let view = CameraView::new(&CameraViewOpts {
+            yaw: 30.0,
+            pitch: 20.0,
+            roll: 10.0,
+        });
+        let position = CameraPosition::from(vec3(1.0, 2.0, 3.0));
+        let back = view.get_back_view(&position);
+        let expected = Mat4::face_toward(position.pos(), position.pos() - view.front, view.up);
+        let columns = [
+            (back.x_axis(), expected.x_axis()),
+            (back.y_axis(), expected.y_axis()),
+            (back.z_axis(), expected.z_axis()),
+            (back.w_axis(), expected.w_axis()),
+        ];
+        for (actual, expected) in columns.iter() {
+            assert!(
+                (*actual - *expected).length() < 0.0001,
+                "{:?} != {:?}",
+                back,
+                expected
+            );
+        }
+        // The view itself is unchanged
+        assert_near_vec(view.front, view.orientation * -Vec3::unit_z());
     }
 }
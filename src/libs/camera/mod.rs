@@ -4,8 +4,23 @@
 //
 
 mod camera;
+pub mod camera_bindings;
+pub mod camera_bookmarks;
+pub mod camera_collision;
 pub mod camera_config;
+pub mod camera_culling;
+pub mod camera_focus;
+pub mod camera_follow;
+#[cfg(feature = "gamepad")]
+pub mod camera_gamepad;
 pub mod camera_info;
+pub mod camera_lens;
+pub mod camera_motion;
+pub mod camera_orbit;
+pub mod camera_path;
+pub mod camera_picking;
 pub mod camera_plugin;
 mod camera_position;
+pub mod camera_scene;
+pub mod camera_switch;
 pub mod camera_view;
@@ -0,0 +1,177 @@
+use bevy::prelude::*;
+use std::{collections::VecDeque, fmt};
+
+/// Frame time statistics over a rolling window of the most recent frames.
+/// All times are in milliseconds.
+#[derive(Debug)]
+pub struct FrameTimeStats {
+    frame_times: VecDeque<f32>,
+    capacity: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameTimeSummary {
+    pub frames: usize,
+    pub min: f32,
+    pub max: f32,
+    pub avg: f32,
+    pub p99: f32,
+    pub fps: f32,
+}
+
+impl FrameTimeStats {
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "frame time window needs to hold at least one frame"
+        );
+        FrameTimeStats {
+            frame_times: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Records the time the last frame took, dropping the oldest frame once the window is full.
+    pub fn push(&mut self, frame_time: f32) {
+        if self.frame_times.len() == self.capacity {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frame_times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame_times.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.frame_times.clear();
+    }
+
+    /// Summarizes the frames in the window, `None` if no frame was recorded yet.
+    pub fn summary(&self) -> Option<FrameTimeSummary> {
+        if self.frame_times.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f32> = self.frame_times.iter().copied().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let frames = sorted.len();
+        let avg = sorted.iter().sum::<f32>() / frames as f32;
+        // Nearest rank percentile
+        let p99_rank = ((0.99 * frames as f32).ceil() as usize).max(1);
+        Some(FrameTimeSummary {
+            frames,
+            min: sorted[0],
+            max: sorted[frames - 1],
+            avg,
+            p99: sorted[p99_rank - 1],
+            fps: if avg > 0.0 { 1000.0 / avg } else { 0.0 },
+        })
+    }
+}
+
+impl Default for FrameTimeStats {
+    fn default() -> Self {
+        FrameTimeStats::with_capacity(120)
+    }
+}
+
+impl fmt::Display for FrameTimeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "FPS: {:.0} (avg: {:.2}ms, min: {:.2}ms, max: {:.2}ms, p99: {:.2}ms)",
+            self.fps, self.avg, self.min, self.max, self.p99
+        )
+    }
+}
+
+fn frame_time_system(time: Res<Time>, mut stats: ResMut<FrameTimeStats>) {
+    // The very first frame has no delta
+    if time.delta_seconds > 0.0 {
+        stats.push(time.delta_seconds * 1000.0);
+    }
+}
+
+/// Records frame times into the `FrameTimeStats` resource.
+pub struct FrameTimePlugin {
+    /// Number of most recent frames the statistics are computed from
+    pub window: usize,
+}
+
+impl Default for FrameTimePlugin {
+    fn default() -> Self {
+        FrameTimePlugin { window: 120 }
+    }
+}
+
+impl Plugin for FrameTimePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_resource(FrameTimeStats::with_capacity(self.window))
+            .add_system_to_stage(stage::FIRST, frame_time_system.system());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_window_has_no_summary() {
+        let mut stats = FrameTimeStats::with_capacity(4);
+        assert!(stats.is_empty());
+        assert_eq!(stats.summary(), None);
+
+        stats.push(16.0);
+        stats.clear();
+        assert_eq!(stats.summary(), None);
+    }
+
+    #[test]
+    fn full_window_drops_the_oldest_frame() {
+        let mut stats = FrameTimeStats::with_capacity(3);
+        for frame_time in [100.0, 10.0, 20.0, 30.0].iter() {
+            stats.push(*frame_time);
+        }
+        assert_eq!(stats.len(), 3);
+
+        let summary = stats.summary().unwrap();
+        assert_eq!(summary.frames, 3);
+        assert_eq!(summary.min, 10.0);
+        assert_eq!(summary.max, 30.0);
+        assert_eq!(summary.avg, 20.0);
+        assert_eq!(summary.fps, 50.0);
+    }
+
+    #[test]
+    fn p99_is_the_nearest_rank() {
+        let mut stats = FrameTimeStats::with_capacity(200);
+        // Out of order, the summary sorts
+        for frame_time in (1..=100).rev() {
+            stats.push(frame_time as f32);
+        }
+        assert_eq!(stats.summary().unwrap().p99, 99.0);
+
+        for frame_time in 101..=200 {
+            stats.push(frame_time as f32);
+        }
+        assert_eq!(stats.summary().unwrap().p99, 198.0);
+
+        // Below a hundred frames the slowest one is the 99th percentile
+        let mut stats = FrameTimeStats::with_capacity(10);
+        for frame_time in 1..=10 {
+            stats.push(frame_time as f32);
+        }
+        assert_eq!(stats.summary().unwrap().p99, 10.0);
+
+        let mut stats = FrameTimeStats::with_capacity(10);
+        stats.push(5.0);
+        let summary = stats.summary().unwrap();
+        assert_eq!(summary.p99, 5.0);
+        assert_eq!(summary.min, summary.max);
+    }
+}
@@ -1,3 +1,5 @@
 pub mod app;
 pub mod camera;
+pub mod frame_time;
+pub mod shape;
 pub mod util;